@@ -0,0 +1,143 @@
+use anyhow::{anyhow, bail, Context, Result};
+use clap::Subcommand;
+use std::{fs::File, path::PathBuf};
+use stowr_core::{
+    asset::{Asset, AssetEvent, AssetId, AssetQueries},
+    db::{SqliteEventLog, SqliteRepository},
+    eventstore::export_history,
+    validation::Name,
+};
+use uuid::Uuid;
+
+use crate::config::DatabaseUrl;
+use crate::csv_file::{read_assets, write_assets};
+use crate::output::{print_asset, print_assets, Format};
+
+#[derive(Subcommand, Debug)]
+pub enum AssetCommand {
+    /// Add a new asset
+    Add {
+        name: Name,
+        /// How many are on hand
+        #[arg(short, long, default_value_t = 0)]
+        quantity: u32,
+    },
+    /// List every asset
+    List,
+    /// Show one asset
+    Get { id: AssetId },
+    /// List assets whose name or description contains a query, ignoring case
+    Search { query: String },
+    /// Remove an asset
+    Rm { id: AssetId },
+    /// Add every `name,quantity,description` row of a CSV file
+    Import { file: PathBuf },
+    /// Write every asset to a CSV file that `import` can read back
+    Export { file: PathBuf },
+    /// Print every event recorded for an asset, oldest first, as JSON
+    History { id: AssetId },
+}
+
+/// The asset store commands run against.
+pub type Assets = Box<dyn AssetQueries + Send + Sync>;
+
+/// Where the assets' histories are recorded.
+pub type Events = SqliteEventLog<AssetEvent>;
+
+/// Connect to the database `db` names.
+pub async fn open(db: &DatabaseUrl) -> Result<Assets> {
+    let url = &db.url;
+    tracing::info!(source = %db.source, url, "using database");
+    let repo = SqliteRepository::<Asset, AssetId>::connect(url)
+        .await
+        .with_context(|| format!("could not open database {url}"))?;
+    Ok(Box::new(repo))
+}
+
+/// Open the event log kept alongside the assets in `db`.
+pub async fn open_events(db: &DatabaseUrl) -> Result<Events> {
+    SqliteEventLog::connect(&db.url)
+        .await
+        .with_context(|| format!("could not open the event log in {}", db.url))
+}
+
+pub async fn run(cmd: AssetCommand, repo: &Assets, events: &Events, format: Format) -> Result<()> {
+    match cmd {
+        AssetCommand::Add { name, quantity } => {
+            let asset = Asset::new(AssetId(Uuid::now_v7()), name, None::<String>, quantity);
+            let asset = create(repo, events, asset).await?;
+            println!("Added {} ({})", asset.name, asset.id);
+        }
+        AssetCommand::List => {
+            let assets = repo.list().await?;
+            print_assets(&assets, format)?;
+        }
+        AssetCommand::Search { query } => {
+            let assets = repo.search(&query).await?;
+            print_assets(&assets, format)?;
+        }
+        AssetCommand::Get { id } => {
+            let asset = repo
+                .fetch(id.clone())
+                .await?
+                .ok_or_else(|| anyhow!("no asset with id {id}"))?;
+            print_asset(&asset, format)?;
+        }
+        AssetCommand::Rm { id } => {
+            repo.delete(id.clone())
+                .await
+                .with_context(|| format!("could not remove asset {id}"))?;
+            println!("Removed {id}");
+        }
+        AssetCommand::Import { file } => {
+            let input =
+                File::open(&file).with_context(|| format!("could not open {}", file.display()))?;
+            let (mut imported, mut failed) = (0, 0);
+            for (line, parsed) in read_assets(input) {
+                let created = match parsed {
+                    Ok(asset) => create(repo, events, asset).await.map_err(|e| e.to_string()),
+                    Err(reason) => Err(reason),
+                };
+                match created {
+                    Ok(_) => imported += 1,
+                    Err(reason) => {
+                        failed += 1;
+                        eprintln!("line {line}: {reason}");
+                    }
+                }
+            }
+            println!("Imported {imported} assets, {failed} failed");
+            if failed > 0 {
+                bail!("{failed} rows of {} were not imported", file.display());
+            }
+        }
+        AssetCommand::Export { file } => {
+            let assets = repo.list().await?;
+            let output = File::create(&file)
+                .with_context(|| format!("could not create {}", file.display()))?;
+            write_assets(output, &assets)?;
+            println!("Exported {} assets to {}", assets.len(), file.display());
+        }
+        AssetCommand::History { id } => {
+            if !repo.exists(id.clone()).await? {
+                bail!("no asset with id {id}");
+            }
+            println!("{}", export_history(events, id.0).await?);
+        }
+    }
+    Ok(())
+}
+
+/// Store the new `asset`, opening its history with its `Created` event.
+///
+/// The event is recorded first: should storing the asset then fail, the
+/// error is the whole story, as `history` ignores events of assets that
+/// aren't stored. The id is new, so no other write can come between.
+async fn create(repo: &Assets, events: &Events, asset: Asset) -> Result<Asset> {
+    let created = AssetEvent::Created {
+        name: asset.name.clone(),
+        quantity: asset.quantity,
+    };
+    events.append(asset.id.0, 0, vec![created]).await?;
+    Ok(repo.create(asset).await?)
+}
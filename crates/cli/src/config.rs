@@ -0,0 +1,86 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::{env, fmt, path::Path};
+
+/// Environment variable naming the database when `--database-url` isn't given.
+pub const DATABASE_URL_ENV: &str = "STOWR_DATABASE_URL";
+
+/// Config file, in the working directory, read when neither is set.
+pub const CONFIG_FILE: &str = "stowr.toml";
+
+/// The database used when nothing names one: a throwaway in-memory store,
+/// gone when the command exits.
+pub const DEFAULT_DATABASE_URL: &str = "sqlite::memory:";
+
+/// Where the database URL came from, highest precedence first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Flag,
+    Env,
+    File,
+    /// Nothing named a database, so [DEFAULT_DATABASE_URL] is used.
+    Default,
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Source::Flag => f.write_str("--database-url"),
+            Source::Env => write!(f, "${DATABASE_URL_ENV}"),
+            Source::File => f.write_str(CONFIG_FILE),
+            Source::Default => f.write_str("the default"),
+        }
+    }
+}
+
+/// The database to use, and which setting chose it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DatabaseUrl {
+    /// A SQLite connection URL.
+    pub url: String,
+    pub source: Source,
+}
+
+/// The settings `stowr.toml` may hold.
+#[derive(Debug, Default, Deserialize)]
+struct FileConfig {
+    database_url: Option<String>,
+}
+
+/// Pick the database from `flag`, then [DATABASE_URL_ENV], then
+/// [CONFIG_FILE], falling back to [DEFAULT_DATABASE_URL].
+pub fn resolve(flag: Option<String>) -> Result<DatabaseUrl> {
+    let env = env::var(DATABASE_URL_ENV)
+        .ok()
+        .filter(|url| !url.is_empty());
+    let (url, source) = if let Some(url) = flag {
+        (url, Source::Flag)
+    } else if let Some(url) = env {
+        (url, Source::Env)
+    } else if let Some(url) = read_file(Path::new(CONFIG_FILE))? {
+        (url, Source::File)
+    } else {
+        (DEFAULT_DATABASE_URL.to_string(), Source::Default)
+    };
+    check(&url).with_context(|| format!("invalid database URL from {source}"))?;
+    Ok(DatabaseUrl { url, source })
+}
+
+/// The `database_url` set in the config file at `path`, if it exists and
+/// sets one.
+fn read_file(path: &Path) -> Result<Option<String>> {
+    let settings = config::Config::builder()
+        .add_source(config::File::from(path).required(false))
+        .build()
+        .and_then(|settings| settings.try_deserialize::<FileConfig>())
+        .with_context(|| format!("could not read {}", path.display()))?;
+    Ok(settings.database_url)
+}
+
+/// Only SQLite is supported; anything else is rejected before connecting.
+fn check(url: &str) -> Result<()> {
+    if !url.starts_with("sqlite:") {
+        bail!("{url:?} is not a SQLite URL, e.g. sqlite://stowr.db?mode=rwc");
+    }
+    Ok(())
+}
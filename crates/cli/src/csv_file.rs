@@ -0,0 +1,66 @@
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::io;
+use stowr_core::{
+    asset::{Asset, AssetId},
+    validation::Name,
+};
+use uuid::Uuid;
+
+/// One `name,quantity,description` CSV row.
+#[derive(Debug, Serialize, Deserialize)]
+struct Row {
+    name: String,
+    quantity: u32,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+/// Parse `name,quantity,description` rows after a header line into new
+/// assets, paired with the line each came from. A bad row is reported as an
+/// `Err` with its reason and doesn't stop the rows after it.
+pub fn read_assets(input: impl io::Read) -> Vec<(u64, Result<Asset, String>)> {
+    let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(input);
+    let headers = match reader.headers() {
+        Ok(headers) => headers.clone(),
+        Err(err) => return vec![(1, Err(err.to_string()))],
+    };
+
+    reader
+        .records()
+        .map(|record| match record {
+            Ok(record) => {
+                let line = record.position().map_or(0, |p| p.line());
+                (line, to_asset(&record, &headers))
+            }
+            Err(err) => (err.position().map_or(0, |p| p.line()), Err(err.to_string())),
+        })
+        .collect()
+}
+
+fn to_asset(record: &csv::StringRecord, headers: &csv::StringRecord) -> Result<Asset, String> {
+    let row: Row = record
+        .deserialize(Some(headers))
+        .map_err(|err| err.to_string())?;
+    let name = Name::new(row.name).map_err(|err| err.to_string())?;
+    Ok(Asset::new(
+        AssetId(Uuid::now_v7()),
+        name,
+        row.description,
+        row.quantity,
+    ))
+}
+
+/// Write `assets` as `name,quantity,description` rows under a header line.
+pub fn write_assets(output: impl io::Write, assets: &[Asset]) -> Result<()> {
+    let mut writer = csv::Writer::from_writer(output);
+    for asset in assets {
+        writer.serialize(Row {
+            name: asset.name.to_string(),
+            quantity: asset.quantity.get(),
+            description: asset.description.clone(),
+        })?;
+    }
+    writer.flush()?;
+    Ok(())
+}
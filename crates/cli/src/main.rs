@@ -1,16 +1,96 @@
-use clap::Parser;
+use anyhow::{anyhow, Result};
+use clap::{ArgAction, CommandFactory, Parser, Subcommand};
+use clap_complete::Shell;
+use std::{io, process::ExitCode};
+use stowr_core::logger::{self, LevelFilter};
+
+mod asset;
+mod config;
+mod csv_file;
+mod output;
 
 /// Welcome to the CLI for Stowr
 #[derive(Parser, Debug)]
 #[command(version, about, long_about = None)]
 struct Args {
-    /// Name of the asset to show
-    #[arg(short, long)]
-    name: String,
+    /// SQLite database to use, as a connection URL. Falls back to
+    /// $STOWR_DATABASE_URL, then `database_url` in ./stowr.toml, then an
+    /// in-memory database
+    #[arg(long, alias = "db", global = true)]
+    database_url: Option<String>,
+
+    /// How to print listings
+    #[arg(long, global = true, value_enum, default_value_t)]
+    format: output::Format,
+
+    /// Log more detail: -v for info, -vv for debug, -vvv for trace
+    #[arg(short, long, action = ArgAction::Count, global = true)]
+    verbose: u8,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Add, list, search, show and remove assets, import and export them as
+    /// CSV, and print their history
+    #[command(subcommand)]
+    Asset(asset::AssetCommand),
+    /// Print a shell completion script
+    #[command(hide = true)]
+    Completions {
+        /// Shell to complete for; defaults to the one in $SHELL
+        shell: Option<Shell>,
+    },
+}
+
+/// Write the completion script for `shell` (or the shell in `$SHELL`).
+fn completions(shell: Option<Shell>) -> Result<()> {
+    let shell = shell
+        .or_else(Shell::from_env)
+        .ok_or_else(|| anyhow!("could not detect your shell; pass one, e.g. `completions bash`"))?;
+    let mut cmd = Args::command();
+    let bin = cmd.get_name().to_string();
+    clap_complete::generate(shell, &mut cmd, bin, &mut io::stdout());
+    Ok(())
+}
+
+/// Run an asset command against the database the settings resolve to.
+async fn assets(
+    cmd: asset::AssetCommand,
+    database_url: Option<String>,
+    format: output::Format,
+) -> Result<()> {
+    let db = config::resolve(database_url)?;
+    let repo = asset::open(&db).await?;
+    let events = asset::open_events(&db).await?;
+    asset::run(cmd, &repo, &events, format).await
+}
+
+fn log_level(verbose: u8) -> LevelFilter {
+    match verbose {
+        0 => LevelFilter::WARN,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    }
 }
 
-fn main() {
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> ExitCode {
     let args = Args::parse();
+    logger::init(log_level(args.verbose));
 
-    println!("Asset [{}] not found...yet!", args.name);
+    let result = match args.command {
+        Command::Asset(cmd) => assets(cmd, args.database_url, args.format).await,
+        Command::Completions { shell } => completions(shell),
+    };
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {err:#}");
+            ExitCode::FAILURE
+        }
+    }
 }
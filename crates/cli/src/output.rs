@@ -0,0 +1,67 @@
+use anyhow::Result;
+use clap::ValueEnum;
+use stowr_core::Asset;
+
+/// How listings are printed to stdout.
+#[derive(ValueEnum, Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Aligned columns for people
+    #[default]
+    Table,
+    /// JSON for scripts
+    Json,
+}
+
+/// Print `assets` as a table with a header row, or as a JSON array.
+pub fn print_assets(assets: &[Asset], format: Format) -> Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(assets)?),
+        Format::Table if assets.is_empty() => println!("No assets."),
+        Format::Table => print!("{}", table(assets)),
+    }
+    Ok(())
+}
+
+/// Print one asset as a single-row table, or as a JSON object.
+pub fn print_asset(asset: &Asset, format: Format) -> Result<()> {
+    match format {
+        Format::Json => println!("{}", serde_json::to_string_pretty(asset)?),
+        Format::Table => print!("{}", table(std::slice::from_ref(asset))),
+    }
+    Ok(())
+}
+
+fn table(assets: &[Asset]) -> String {
+    let header = ["ID", "NAME", "QUANTITY"];
+    let rows: Vec<[String; 3]> = assets
+        .iter()
+        .map(|a| [a.id.to_string(), a.name.to_string(), a.quantity.to_string()])
+        .collect();
+
+    let mut widths = header.map(str::len);
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut out = String::new();
+    let mut push_row = |cells: [&str; 3]| {
+        let line = format!(
+            "{:<w0$}  {:<w1$}  {:>w2$}",
+            cells[0],
+            cells[1],
+            cells[2],
+            w0 = widths[0],
+            w1 = widths[1],
+            w2 = widths[2],
+        );
+        out.push_str(line.trim_end());
+        out.push('\n');
+    };
+    push_row(header);
+    for row in &rows {
+        push_row([&row[0], &row[1], &row[2]]);
+    }
+    out
+}
@@ -0,0 +1,361 @@
+use assert_cmd::Command;
+use stowr_core::{asset::AssetId, db::SqliteRepository, Asset, Repository};
+use tempfile::TempDir;
+
+/// The URL of the database file `file` in `dir`.
+fn db_url(dir: &TempDir, file: &str) -> String {
+    format!("sqlite://{}?mode=rwc", dir.path().join(file).display())
+}
+
+/// A `stowr-cli` invocation in `dir`, with no database chosen yet and logs
+/// left uncoloured.
+fn bare_stowr(dir: &TempDir) -> Command {
+    let mut cmd = Command::cargo_bin("stowr-cli").unwrap();
+    cmd.current_dir(dir.path())
+        .env_remove("STOWR_DATABASE_URL")
+        .env("NO_COLOR", "1");
+    cmd
+}
+
+/// A `stowr-cli` invocation against a fresh database in `dir`.
+fn stowr(dir: &TempDir) -> Command {
+    let mut cmd = bare_stowr(dir);
+    cmd.args(["--database-url", &db_url(dir, "stowr.db")]);
+    cmd
+}
+
+fn stdout(cmd: &mut Command) -> String {
+    let out = cmd.assert().success().get_output().stdout.clone();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn add_then_list_shows_the_asset() {
+    let dir = TempDir::new().unwrap();
+    let added = stdout(stowr(&dir).args(["asset", "add", "Cordless drill", "--quantity", "2"]));
+    assert!(added.starts_with("Added Cordless drill"));
+
+    let listed = stdout(stowr(&dir).args(["asset", "list"]));
+    assert!(listed.contains("Cordless drill"));
+}
+
+/// Every asset in the database, read back through `--format json`.
+fn list_json(dir: &TempDir) -> Vec<Asset> {
+    let listed = stdout(stowr(dir).args(["--format", "json", "asset", "list"]));
+    serde_json::from_str(&listed).unwrap()
+}
+
+#[test]
+fn json_list_parses_back_into_assets() {
+    let dir = TempDir::new().unwrap();
+    stowr(&dir)
+        .args(["asset", "add", "Drill", "-q", "2"])
+        .assert()
+        .success();
+    stowr(&dir).args(["asset", "add", "Saw"]).assert().success();
+
+    let assets = list_json(&dir);
+    let names: Vec<_> = assets.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, vec!["Drill", "Saw"]);
+    assert_eq!(assets[0].quantity.get(), 2);
+
+    let raw = stdout(stowr(&dir).args(["asset", "list", "--format", "json"]));
+    let json: serde_json::Value = serde_json::from_str(&raw).unwrap();
+    assert_eq!(json[0]["id"], assets[0].id.to_string());
+}
+
+#[test]
+fn table_list_has_name_and_quantity_columns() {
+    let dir = TempDir::new().unwrap();
+    stowr(&dir)
+        .args(["asset", "add", "Cordless drill", "-q", "12"])
+        .assert()
+        .success();
+
+    let listed = stdout(stowr(&dir).args(["asset", "list"]));
+    let mut lines = listed.lines();
+    let header: Vec<_> = lines.next().unwrap().split_whitespace().collect();
+    assert_eq!(header, vec!["ID", "NAME", "QUANTITY"]);
+    let row = lines.next().unwrap();
+    assert!(row.contains("Cordless drill"));
+    assert!(row.ends_with("12"));
+}
+
+#[test]
+fn get_and_rm_by_id() {
+    let dir = TempDir::new().unwrap();
+    stowr(&dir).args(["asset", "add", "Saw"]).assert().success();
+    let id = list_json(&dir)[0].id.to_string();
+
+    let shown = stdout(stowr(&dir).args(["asset", "get", &id]));
+    assert!(shown.lines().nth(1).unwrap().contains("Saw"));
+    let shown = stdout(stowr(&dir).args(["--format", "json", "asset", "get", &id]));
+    let asset: Asset = serde_json::from_str(&shown).unwrap();
+    assert_eq!(asset.name, "Saw");
+
+    stowr(&dir).args(["asset", "rm", &id]).assert().success();
+    stowr(&dir).args(["asset", "get", &id]).assert().failure();
+    stowr(&dir).args(["asset", "rm", &id]).assert().failure();
+}
+
+#[test]
+fn empty_list_and_bad_id() {
+    let dir = TempDir::new().unwrap();
+    assert_eq!(stdout(stowr(&dir).args(["asset", "list"])), "No assets.\n");
+    stowr(&dir)
+        .args(["asset", "get", "not-an-id"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn blank_names_are_rejected() {
+    let dir = TempDir::new().unwrap();
+    let out = stowr(&dir)
+        .args(["asset", "add", " "])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    assert!(String::from_utf8(out)
+        .unwrap()
+        .contains("name must not be empty"));
+}
+
+#[test]
+fn search_matches_names_ignoring_case() {
+    let dir = TempDir::new().unwrap();
+    for name in ["Cordless drill", "Table saw", "Drill bits"] {
+        stowr(&dir).args(["asset", "add", name]).assert().success();
+    }
+
+    let found = stdout(stowr(&dir).args(["--format", "json", "asset", "search", "DRILL"]));
+    let found: Vec<Asset> = serde_json::from_str(&found).unwrap();
+    let names: Vec<_> = found.iter().map(|a| a.name.as_str()).collect();
+    assert_eq!(names, vec!["Cordless drill", "Drill bits"]);
+
+    let none = stdout(stowr(&dir).args(["asset", "search", "hammer"]));
+    assert_eq!(none, "No assets.\n");
+}
+
+#[test]
+fn csv_export_then_import_round_trips() {
+    let dir = TempDir::new().unwrap();
+    stowr(&dir)
+        .args(["asset", "add", "Drill", "-q", "2"])
+        .assert()
+        .success();
+    stowr(&dir)
+        .args(["asset", "add", "Saw, table"])
+        .assert()
+        .success();
+
+    let file = dir.path().join("assets.csv");
+    let file = file.to_str().unwrap();
+    stowr(&dir)
+        .args(["asset", "export", file])
+        .assert()
+        .success();
+    let csv = std::fs::read_to_string(file).unwrap();
+    assert!(csv.starts_with("name,quantity,description\n"));
+
+    let other = TempDir::new().unwrap();
+    let report = stdout(stowr(&other).args(["asset", "import", file]));
+    assert_eq!(report, "Imported 2 assets, 0 failed\n");
+
+    let assets = list_json(&other);
+    let rows: Vec<_> = assets
+        .iter()
+        .map(|a| (a.name.as_str(), a.quantity.get()))
+        .collect();
+    assert_eq!(rows, vec![("Drill", 2), ("Saw, table", 0)]);
+}
+
+#[test]
+fn csv_import_reports_bad_rows_and_keeps_going() {
+    let dir = TempDir::new().unwrap();
+    let file = dir.path().join("assets.csv");
+    std::fs::write(
+        &file,
+        "name,quantity,description\nDrill,2,cordless\nSaw,lots,\n,1,\nRake,1\n",
+    )
+    .unwrap();
+
+    let out = stowr(&dir)
+        .args(["asset", "import", file.to_str().unwrap()])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(stdout, "Imported 2 assets, 2 failed\n");
+    assert!(stderr.contains("line 3:"));
+    assert!(stderr.contains("line 4: name must not be empty"));
+
+    let names: Vec<_> = list_json(&dir).into_iter().map(|a| a.name).collect();
+    assert_eq!(names, vec!["Drill", "Rake"]);
+}
+
+#[test]
+fn bash_completions_cover_the_subcommands() {
+    let dir = TempDir::new().unwrap();
+    let script = stdout(stowr(&dir).args(["completions", "bash"]));
+    assert!(script.contains("stowr-cli"));
+    assert!(script.contains("asset"));
+    assert!(script.contains("import"));
+}
+
+/// The stderr of a successful `-v asset list`, which logs the database used.
+fn database_log(cmd: &mut Command) -> String {
+    let out = cmd
+        .args(["-v", "asset", "list"])
+        .assert()
+        .success()
+        .get_output()
+        .stderr
+        .clone();
+    String::from_utf8(out).unwrap()
+}
+
+#[test]
+fn database_url_flag_beats_env_beats_config_file() {
+    let dir = TempDir::new().unwrap();
+    let (flag, env, file) = (
+        db_url(&dir, "flag.db"),
+        db_url(&dir, "env.db"),
+        db_url(&dir, "file.db"),
+    );
+    std::fs::write(
+        dir.path().join("stowr.toml"),
+        format!("database_url = {file:?}\n"),
+    )
+    .unwrap();
+
+    let log = database_log(
+        bare_stowr(&dir)
+            .env("STOWR_DATABASE_URL", &env)
+            .args(["--database-url", &flag]),
+    );
+    assert!(log.contains("source=--database-url"), "{log}");
+    assert!(log.contains("flag.db"), "{log}");
+
+    let log = database_log(bare_stowr(&dir).env("STOWR_DATABASE_URL", &env));
+    assert!(log.contains("source=$STOWR_DATABASE_URL"), "{log}");
+    assert!(log.contains("env.db"), "{log}");
+
+    let log = database_log(&mut bare_stowr(&dir));
+    assert!(log.contains("source=stowr.toml"), "{log}");
+    bare_stowr(&dir)
+        .args(["asset", "add", "Saw"])
+        .assert()
+        .success();
+    let listed = stdout(bare_stowr(&dir).args(["--database-url", &file, "asset", "list"]));
+    assert!(listed.contains("Saw"));
+}
+
+#[test]
+fn no_database_setting_uses_a_throwaway_in_memory_store() {
+    let dir = TempDir::new().unwrap();
+    let log = database_log(&mut bare_stowr(&dir));
+    assert!(log.contains("source=the default"), "{log}");
+    assert!(log.contains("sqlite::memory:"), "{log}");
+
+    bare_stowr(&dir)
+        .args(["asset", "add", "Saw"])
+        .assert()
+        .success();
+    let listed = stdout(bare_stowr(&dir).args(["asset", "list"]));
+    assert_eq!(listed, "No assets.\n");
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 0);
+}
+
+#[test]
+fn invalid_database_urls_are_reported_with_their_source() {
+    let dir = TempDir::new().unwrap();
+    let out = bare_stowr(&dir)
+        .env("STOWR_DATABASE_URL", "postgres://localhost/stowr")
+        .args(["asset", "list"])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    let err = String::from_utf8(out).unwrap();
+    assert!(
+        err.contains("invalid database URL from $STOWR_DATABASE_URL"),
+        "{err}"
+    );
+    assert!(err.contains("is not a SQLite URL"), "{err}");
+}
+
+#[test]
+fn added_assets_start_their_history() {
+    let dir = TempDir::new().unwrap();
+    stowr(&dir)
+        .args(["asset", "add", "Saw", "-q", "4"])
+        .assert()
+        .success();
+    let id = list_json(&dir)[0].id.to_string();
+
+    let history = stdout(stowr(&dir).args(["asset", "history", &id]));
+    let history: Vec<serde_json::Value> = serde_json::from_str(&history).unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["sequence"], 1);
+    assert_eq!(
+        history[0]["event"],
+        serde_json::json!({ "Created": { "name": "Saw", "quantity": 4 } })
+    );
+}
+
+#[test]
+fn history_of_an_unknown_asset_says_so() {
+    let dir = TempDir::new().unwrap();
+    let id = uuid::Uuid::now_v7().to_string();
+    let out = stowr(&dir)
+        .args(["asset", "history", &id])
+        .assert()
+        .failure()
+        .get_output()
+        .stderr
+        .clone();
+    let err = String::from_utf8(out).unwrap();
+    assert!(err.contains(&format!("no asset with id {id}")), "{err}");
+}
+
+#[test]
+fn history_of_an_asset_without_events_says_so() {
+    let dir = TempDir::new().unwrap();
+    // Stored straight through the repository, as before histories were kept.
+    let asset = Asset::new(
+        AssetId(uuid::Uuid::now_v7()),
+        "Saw".parse().unwrap(),
+        None::<String>,
+        0u32,
+    );
+    let url = db_url(&dir, "stowr.db");
+    tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(async {
+            let repo = SqliteRepository::<Asset, AssetId>::connect(&url).await?;
+            repo.create(asset.clone()).await
+        })
+        .unwrap();
+    let id = asset.id.to_string();
+
+    let out = stowr(&dir)
+        .args(["asset", "history", &id])
+        .assert()
+        .failure()
+        .get_output()
+        .clone();
+    assert!(out.stdout.is_empty());
+    let err = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        err.contains(&format!("no events recorded for {id}")),
+        "{err}"
+    );
+}
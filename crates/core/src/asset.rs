@@ -1,7 +1,21 @@
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::{
+    fmt,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
 use uuid::Uuid;
 
-use crate::common::Repository;
+use stowr_macro::{command, domain_impl};
+
+use crate::common::{
+    unix_epoch, Aggregate, AggregateError, DomainInfo, FieldInfo, Identifiable, MergeableEntity,
+    Named, Repository, SoftDeletable,
+};
+use crate::error::{IdParseError, QuantityError, RepositoryError};
+use crate::location::LocationId;
+use crate::validation::Name;
 
 /// Abstract persistence operations for [`Asset`].
 ///
@@ -9,21 +23,513 @@ use crate::common::Repository;
 /// exposing any database implementation details to the rest of the domain code.
 pub trait AssetRepository: Repository<Entity = Asset, Id = AssetId> {}
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Structured filters for [AssetQueries::find],
+/// built up fluently, e.g. `Query::new().name_contains("drill").quantity_gte(2)`.
+/// An [Asset] must pass every filter that is set; an empty query matches all.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Query {
+    name_contains: Option<String>,
+    quantity_gte: Option<u32>,
+    quantity_lte: Option<u32>,
+    location_id: Option<LocationId>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The name contains `s`, ignoring case.
+    pub fn name_contains(mut self, s: impl Into<String>) -> Self {
+        self.name_contains = Some(s.into().to_lowercase());
+        self
+    }
+
+    /// At least `n` are on hand.
+    pub fn quantity_gte(mut self, n: u32) -> Self {
+        self.quantity_gte = Some(n);
+        self
+    }
+
+    /// At most `n` are on hand.
+    pub fn quantity_lte(mut self, n: u32) -> Self {
+        self.quantity_lte = Some(n);
+        self
+    }
+
+    /// The asset is kept at `id`.
+    pub fn in_location(mut self, id: LocationId) -> Self {
+        self.location_id = Some(id);
+        self
+    }
+
+    /// Whether `asset` passes every filter.
+    pub fn matches(&self, asset: &Asset) -> bool {
+        let quantity = asset.quantity.get();
+        self.name_contains
+            .as_ref()
+            .is_none_or(|s| asset.name.to_lowercase().contains(s))
+            && self.quantity_gte.is_none_or(|n| quantity >= n)
+            && self.quantity_lte.is_none_or(|n| quantity <= n)
+            && self
+                .location_id
+                .as_ref()
+                .is_none_or(|id| asset.location_id.as_ref() == Some(id))
+    }
+}
+
+/// Asset lookups beyond fetching by id.
+#[async_trait]
+pub trait AssetQueries: Repository<Entity = Asset, Id = AssetId> {
+    /// Every [Asset] stored at `loc`.
+    async fn by_location(&self, loc: LocationId) -> Result<Vec<Asset>, RepositoryError>;
+
+    /// Every [Asset] for which [Asset::matches] `q`.
+    async fn search(&self, q: &str) -> Result<Vec<Asset>, RepositoryError>;
+
+    /// Every [Asset] that passes all of `q`'s filters.
+    async fn find(&self, q: Query) -> Result<Vec<Asset>, RepositoryError>;
+
+    /// Move every asset in `ids` to `to`, one update each. An id that can't
+    /// be moved (e.g. it isn't stored) is reported in [BulkMoveReport::failed]
+    /// and the rest are still moved.
+    async fn bulk_move(
+        &self,
+        ids: &[AssetId],
+        to: LocationId,
+    ) -> Result<BulkMoveReport, RepositoryError> {
+        let mut report = BulkMoveReport::default();
+        for id in ids {
+            let moved = match self.fetch(id.clone()).await {
+                Ok(Some(mut asset)) => {
+                    asset.location_id = Some(to.clone());
+                    self.update(asset).await.map(|_| ())
+                }
+                Ok(None) => Err(RepositoryError::NotFound),
+                Err(err) => Err(err),
+            };
+            match moved {
+                Ok(()) => report.succeeded.push(id.clone()),
+                Err(err) => report.failed.push((id.clone(), err)),
+            }
+        }
+        Ok(report)
+    }
+}
+
+/// The outcome of [AssetQueries::bulk_move], per id and in the order given.
+#[derive(Debug, Default)]
+pub struct BulkMoveReport {
+    /// Assets now at the destination.
+    pub succeeded: Vec<AssetId>,
+    /// Assets that weren't moved, and why.
+    pub failed: Vec<(AssetId, RepositoryError)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Asset {
     pub id: AssetId,
-    pub name: String,
+    pub name: Name,
+    /// Free-form notes; omitted from JSON when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Defaults to 0 for assets stored before quantities were tracked.
+    #[serde(default)]
+    pub quantity: Quantity,
+    /// Where the asset is kept, if anywhere yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location_id: Option<LocationId>,
+    /// Bumped by the repository on every update; see [Identifiable::version].
+    #[serde(default)]
+    pub version: u64,
+    /// Set instead of removing the entity when it is soft-deleted.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::common::rfc3339::option"
+    )]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>, format = DateTime))]
+    pub deleted_at: Option<SystemTime>,
+    /// When the repository first stored the entity, as RFC 3339.
+    #[serde(default = "unix_epoch", with = "crate::common::rfc3339")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, format = DateTime))]
+    pub created_at: SystemTime,
+    /// When the repository last stored the entity, as RFC 3339.
+    #[serde(default = "unix_epoch", with = "crate::common::rfc3339")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, format = DateTime))]
+    pub updated_at: SystemTime,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct AssetId(pub Uuid);
 
+/// How many of an [Asset] are on hand; never negative.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
+pub struct Quantity(u32);
+
+impl Quantity {
+    pub fn new(n: u32) -> Self {
+        Quantity(n)
+    }
+
+    pub fn get(self) -> u32 {
+        self.0
+    }
+
+    /// `self + by`, or [QuantityError::Overflow] past `u32::MAX`.
+    pub fn increment(self, by: u32) -> Result<Self, QuantityError> {
+        self.0
+            .checked_add(by)
+            .map(Quantity)
+            .ok_or(QuantityError::Overflow)
+    }
+
+    /// `self - by`, or [QuantityError::Negative] if that would go below zero.
+    pub fn decrement(self, by: u32) -> Result<Self, QuantityError> {
+        self.0
+            .checked_sub(by)
+            .map(Quantity)
+            .ok_or(QuantityError::Negative)
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl proptest::arbitrary::Arbitrary for Quantity {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::prelude::*;
+        any::<u32>().prop_map(Quantity).boxed()
+    }
+}
+
+impl From<u32> for Quantity {
+    fn from(n: u32) -> Self {
+        Quantity(n)
+    }
+}
+
+impl TryFrom<i64> for Quantity {
+    type Error = QuantityError;
+    fn try_from(n: i64) -> Result<Self, Self::Error> {
+        if n < 0 {
+            return Err(QuantityError::Negative);
+        }
+        u32::try_from(n)
+            .map(Quantity)
+            .map_err(|_| QuantityError::Overflow)
+    }
+}
+
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(any(test, feature = "testing"))]
+impl proptest::arbitrary::Arbitrary for AssetId {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::prelude::*;
+        any::<[u8; 16]>()
+            .prop_map(|b| AssetId(Uuid::from_bytes(b)))
+            .boxed()
+    }
+}
+
+/// Any field values a repository could hand back, timestamps included.
+#[cfg(any(test, feature = "testing"))]
+impl proptest::arbitrary::Arbitrary for Asset {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use crate::common::any_time;
+        use proptest::prelude::*;
+        (
+            any::<AssetId>(),
+            any::<Name>(),
+            any::<Option<String>>(),
+            any::<Quantity>(),
+            any::<Option<LocationId>>(),
+            any::<u64>(),
+            proptest::option::of(any_time()),
+            any_time(),
+            any_time(),
+        )
+            .prop_map(
+                |(
+                    id,
+                    name,
+                    description,
+                    quantity,
+                    location_id,
+                    version,
+                    deleted_at,
+                    created_at,
+                    updated_at,
+                )| Asset {
+                    id,
+                    name,
+                    description,
+                    quantity,
+                    location_id,
+                    version,
+                    deleted_at,
+                    created_at,
+                    updated_at,
+                },
+            )
+            .boxed()
+    }
+}
+
+impl fmt::Display for AssetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl FromStr for AssetId {
+    type Err = IdParseError;
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Uuid::from_str(s).map(AssetId).map_err(IdParseError)
+    }
+}
+
+impl From<Uuid> for AssetId {
+    fn from(uuid: Uuid) -> Self {
+        AssetId(uuid)
+    }
+}
+
+impl From<AssetId> for Uuid {
+    fn from(id: AssetId) -> Uuid {
+        id.0
+    }
+}
+
 impl Asset {
-    fn new(id: AssetId, name: impl Into<String>) -> Asset {
+    pub fn new(
+        id: AssetId,
+        name: Name,
+        description: Option<impl Into<String>>,
+        quantity: impl Into<Quantity>,
+    ) -> Asset {
         Asset {
             id,
-            name: name.into(),
+            name,
+            description: description.map(Into::into),
+            quantity: quantity.into(),
+            location_id: None,
+            version: 0,
+            deleted_at: None,
+            created_at: UNIX_EPOCH,
+            updated_at: UNIX_EPOCH,
+        }
+    }
+
+    /// Case-insensitive substring match of `q` against the name or description.
+    pub fn matches(&self, q: &str) -> bool {
+        let q = q.to_lowercase();
+        self.name.to_lowercase().contains(&q)
+            || self
+                .description
+                .as_ref()
+                .is_some_and(|d| d.to_lowercase().contains(&q))
+    }
+}
+
+#[domain_impl]
+impl Asset {
+    /// Start the asset off with `name` and `quantity`. Its `Created` event
+    /// comes first in the asset's log, so the log accounts for everything on
+    /// hand rather than only the changes since.
+    #[command(event = Created)]
+    pub fn create(&mut self, name: Name, quantity: Quantity) {
+        self.name = name;
+        self.quantity = quantity;
+    }
+
+    /// Give the asset a new name.
+    #[command(event = Renamed)]
+    pub fn rename(&mut self, name: Name) {
+        self.name = name;
+    }
+
+    /// Put the asset at `location_id`. Moving to the location it is already
+    /// at is not rejected; it still emits `MovedTo` so the move is recorded.
+    #[command(event = MovedTo)]
+    pub fn move_to(&mut self, location_id: LocationId) {
+        self.location_id = Some(location_id);
+    }
+
+    /// Add `delta` (which may be negative) to the quantity on hand. Rejected
+    /// with [QuantityError::Negative] rather than going below zero.
+    #[command(event = QuantityAdjusted, validate = can_adjust_quantity)]
+    pub fn adjust_quantity(&mut self, delta: i64) -> Result<(), QuantityError> {
+        self.quantity = self.adjusted_quantity(delta)?;
+        Ok(())
+    }
+
+    fn can_adjust_quantity(&self, delta: &i64) -> Result<(), QuantityError> {
+        self.adjusted_quantity(*delta).map(drop)
+    }
+
+    fn adjusted_quantity(&self, delta: i64) -> Result<Quantity, QuantityError> {
+        let next = i64::from(self.quantity.get())
+            .checked_add(delta)
+            .ok_or(QuantityError::Overflow)?;
+        Quantity::try_from(next)
+    }
+
+    /// Take `n` more into stock. Never rejected; the quantity stops at
+    /// `u32::MAX` rather than overflowing.
+    #[command(event = Received)]
+    pub fn receive(&mut self, n: u32) {
+        self.quantity = Quantity::new(self.quantity.get().saturating_add(n));
+    }
+
+    /// Hand `n` out of stock. Rejected with [QuantityError::Negative] when
+    /// fewer than `n` are on hand.
+    #[command(event = Issued, validate = can_issue)]
+    pub fn issue(&mut self, n: u32) -> Result<(), AggregateError> {
+        self.quantity = self.quantity.decrement(n)?;
+        Ok(())
+    }
+
+    fn can_issue(&self, n: &u32) -> Result<(), AggregateError> {
+        self.quantity.decrement(*n)?;
+        Ok(())
+    }
+}
+
+// Asset isn't a `#[domain]` struct, so it registers itself by hand; a test in
+// `common` checks the list against the fields Asset serializes
+inventory::submit! {
+    DomainInfo {
+        name: "Asset",
+        fields: &[
+            FieldInfo { name: "id", ty: "AssetId" },
+            FieldInfo { name: "name", ty: "Name" },
+            FieldInfo { name: "description", ty: "Option<String>" },
+            FieldInfo { name: "quantity", ty: "Quantity" },
+            FieldInfo { name: "location_id", ty: "Option<LocationId>" },
+            FieldInfo { name: "version", ty: "u64" },
+            FieldInfo { name: "deleted_at", ty: "Option<SystemTime>" },
+            FieldInfo { name: "created_at", ty: "SystemTime" },
+            FieldInfo { name: "updated_at", ty: "SystemTime" },
+        ],
+    }
+}
+
+impl Identifiable for Asset {
+    type Id = AssetId;
+
+    fn id(&self) -> Uuid {
+        self.id.0
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+
+    fn created_at(&self) -> Option<SystemTime> {
+        Some(self.created_at)
+    }
+
+    fn set_timestamps(&mut self, created_at: SystemTime, updated_at: SystemTime) {
+        self.created_at = created_at;
+        self.updated_at = updated_at;
+    }
+}
+
+impl Named for Asset {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+/// `other`'s change in quantity since `base` is added to the stored one,
+/// kept between 0 and `u32::MAX`. The name, description and location are
+/// taken from `other` where it changed them, and kept otherwise.
+impl MergeableEntity for Asset {
+    fn merge(&self, base: &Self, other: &Self) -> Self {
+        fn pick<T: Clone + PartialEq>(stored: &T, base: &T, other: &T) -> T {
+            if other == base { stored } else { other }.clone()
         }
+        let delta = i64::from(other.quantity.get()) - i64::from(base.quantity.get());
+        let quantity = (i64::from(self.quantity.get()) + delta).clamp(0, i64::from(u32::MAX));
+        Asset {
+            name: pick(&self.name, &base.name, &other.name),
+            description: pick(&self.description, &base.description, &other.description),
+            quantity: Quantity::new(quantity as u32),
+            location_id: pick(&self.location_id, &base.location_id, &other.location_id),
+            ..self.clone()
+        }
+    }
+}
+
+impl SoftDeletable for Asset {
+    fn deleted_at(&self) -> Option<SystemTime> {
+        self.deleted_at
+    }
+
+    fn set_deleted_at(&mut self, at: Option<SystemTime>) {
+        self.deleted_at = at;
+    }
+}
+
+#[test]
+fn merge_applies_changes_since_the_base_and_keeps_the_stored_version() {
+    let mut base = Asset::new(
+        AssetId(Uuid::new_v4()),
+        "drill".parse().unwrap(),
+        None::<String>,
+        2u32,
+    );
+    base.version = 1;
+    let mut stored = base.clone();
+    stored.version = 3;
+    stored.quantity = Quantity::new(4);
+    stored.description = Some("18V".into());
+    let mut other = base.clone();
+    other.name = "Cordless drill".parse().unwrap();
+    other.quantity = Quantity::new(5);
+
+    let merged = stored.merge(&base, &other);
+    assert_eq!(merged.name, "Cordless drill");
+    assert_eq!(merged.description.as_deref(), Some("18V"));
+    assert_eq!(merged.quantity, Quantity::new(7));
+    assert_eq!(merged.version, 3);
+
+    other.quantity = Quantity::new(u32::MAX);
+    assert_eq!(
+        stored.merge(&base, &other).quantity,
+        Quantity::new(u32::MAX)
+    );
+    other.quantity = Quantity::new(0);
+    stored.quantity = Quantity::new(1);
+    assert_eq!(stored.merge(&base, &other).quantity, Quantity::new(0));
+}
+
+#[cfg(test)]
+proptest::proptest! {
+    #[test]
+    fn json_round_trips_any_asset(asset: Asset) {
+        let json = serde_json::to_string(&asset).unwrap();
+        proptest::prop_assert_eq!(serde_json::from_str::<Asset>(&json).unwrap(), asset);
     }
 }
 
@@ -31,7 +537,262 @@ impl Asset {
 fn creates_asset() {
     let id = AssetId(Uuid::new_v4());
     let name = "test";
-    let asset = Asset::new(id.clone(), name);
+    let asset = Asset::new(id.clone(), name.parse().unwrap(), None::<String>, 3u32);
     assert_eq!(asset.id, id);
     assert_eq!(asset.name, "test");
+    assert_eq!(asset.description, None);
+    assert_eq!(asset.quantity, Quantity::new(3));
+}
+
+#[test]
+fn asset_description_round_trips() {
+    let asset = Asset::new(
+        AssetId(Uuid::nil()),
+        "drill".parse().unwrap(),
+        Some("cordless"),
+        1u32,
+    );
+    assert_eq!(asset.description.as_deref(), Some("cordless"));
+    let json = serde_json::to_string(&asset).unwrap();
+    let back: Asset = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.description.as_deref(), Some("cordless"));
+
+    let bare = Asset::new(
+        AssetId(Uuid::nil()),
+        "drill".parse().unwrap(),
+        None::<String>,
+        1u32,
+    );
+    let json = serde_json::to_value(&bare).unwrap();
+    assert!(json.get("description").is_none());
+    let back: Asset = serde_json::from_value(json).unwrap();
+    assert_eq!(back.description, None);
+}
+
+#[test]
+fn matches_name_or_description_ignoring_case() {
+    let asset = Asset::new(
+        AssetId(Uuid::nil()),
+        "Cordless Drill".parse().unwrap(),
+        Some("18V Makita"),
+        1u32,
+    );
+    assert!(asset.matches("drill"));
+    assert!(asset.matches("DRILL"));
+    assert!(asset.matches("makita"));
+    assert!(!asset.matches("saw"));
+}
+
+#[test]
+fn asset_id_parses_from_its_display() {
+    let id = AssetId(Uuid::new_v4());
+    assert_eq!(id.to_string().parse::<AssetId>().unwrap(), id);
+    assert!("not-a-uuid".parse::<AssetId>().is_err());
+}
+
+#[test]
+fn quantity_rejects_negatives() {
+    assert_eq!(Quantity::try_from(-1i64), Err(QuantityError::Negative));
+    assert_eq!(Quantity::try_from(0i64), Ok(Quantity::new(0)));
+    assert_eq!(Quantity::try_from(7i64), Ok(Quantity::new(7)));
+    assert_eq!(
+        Quantity::try_from(i64::from(u32::MAX) + 1),
+        Err(QuantityError::Overflow)
+    );
+}
+
+#[test]
+fn quantity_decrements_to_but_not_below_zero() {
+    let q = Quantity::new(2);
+    assert_eq!(q.decrement(2), Ok(Quantity::new(0)));
+    assert_eq!(q.decrement(3), Err(QuantityError::Negative));
+    assert_eq!(q.increment(1), Ok(Quantity::new(3)));
+    assert_eq!(
+        Quantity::new(u32::MAX).increment(1),
+        Err(QuantityError::Overflow)
+    );
+}
+
+#[test]
+fn quantity_serializes_as_a_number() {
+    let asset = Asset::new(
+        AssetId(Uuid::nil()),
+        "drill".parse().unwrap(),
+        None::<String>,
+        4u32,
+    );
+    let json = serde_json::to_value(&asset).unwrap();
+    assert_eq!(json["quantity"], 4);
+    assert!(serde_json::from_str::<Quantity>("-1").is_err());
+}
+
+/// A known [Asset] and its JSON, which clients depend on; changing the
+/// literal is changing the wire format.
+#[cfg(test)]
+fn contract_asset() -> (Asset, &'static str) {
+    let asset = Asset::new(
+        "0199e1a2-6f3c-7d10-a4b2-3c5e8f9d0a11".parse().unwrap(),
+        "Cordless drill".parse().unwrap(),
+        None::<String>,
+        2u32,
+    );
+    let json = concat!(
+        r#"{"id":"0199e1a2-6f3c-7d10-a4b2-3c5e8f9d0a11","name":"Cordless drill","quantity":2,"#,
+        r#""version":0,"created_at":"1970-01-01T00:00:00Z","#,
+        r#""updated_at":"1970-01-01T00:00:00Z"}"#,
+    );
+    (asset, json)
+}
+
+#[test]
+fn asset_serializes_to_the_json_contract() {
+    let (asset, json) = contract_asset();
+    assert_eq!(serde_json::to_string(&asset).unwrap(), json);
+}
+
+#[test]
+fn asset_deserializes_from_the_json_contract() {
+    let (asset, json) = contract_asset();
+    assert_eq!(serde_json::from_str::<Asset>(json).unwrap(), asset);
+}
+
+#[test]
+fn asset_json_from_before_quantities_has_none_on_hand() {
+    let json = r#"{"id":"0199e1a2-6f3c-7d10-a4b2-3c5e8f9d0a11","name":"Cordless drill"}"#;
+    let asset: Asset = serde_json::from_str(json).unwrap();
+    assert_eq!(asset.quantity, Quantity::new(0));
+    assert_eq!(asset.name, "Cordless drill");
+}
+
+#[test]
+fn asset_json_with_the_old_timestamp_form_still_reads() {
+    use std::time::Duration;
+    let json = concat!(
+        r#"{"id":"0199e1a2-6f3c-7d10-a4b2-3c5e8f9d0a11","name":"Cordless drill","#,
+        r#""created_at":{"secs_since_epoch":1,"nanos_since_epoch":500000000},"#,
+        r#""deleted_at":{"secs_since_epoch":2,"nanos_since_epoch":0}}"#,
+    );
+    let asset: Asset = serde_json::from_str(json).unwrap();
+    assert_eq!(asset.created_at, UNIX_EPOCH + Duration::from_millis(1500));
+    assert_eq!(asset.deleted_at, Some(UNIX_EPOCH + Duration::from_secs(2)));
+
+    let json = serde_json::to_value(&asset).unwrap();
+    assert_eq!(json["created_at"], "1970-01-01T00:00:01.5Z");
+    assert_eq!(json["deleted_at"], "1970-01-01T00:00:02Z");
+}
+
+#[test]
+fn asset_json_ignores_unknown_fields() {
+    let (asset, json) = contract_asset();
+    let extended = json.replacen('{', r#"{"colour":"red","#, 1);
+    assert_eq!(serde_json::from_str::<Asset>(&extended).unwrap(), asset);
+}
+
+#[test]
+fn move_to_emits_moved_to_and_updates_location() {
+    let mut asset = Asset::new(
+        AssetId(Uuid::new_v4()),
+        "drill".parse().unwrap(),
+        None::<String>,
+        1u32,
+    );
+    let shed = LocationId::new();
+
+    let events = asset
+        .handle_command(AssetCommand::MoveTo {
+            location_id: shed.clone(),
+        })
+        .unwrap();
+    assert!(matches!(&events[..], [AssetEvent::MovedTo { location_id }] if *location_id == shed));
+
+    asset.apply_event(&events[0]).unwrap();
+    assert_eq!(asset.location_id, Some(shed.clone()));
+
+    // Moving to where it already is still records the move.
+    let again = asset
+        .handle_command(AssetCommand::MoveTo { location_id: shed })
+        .unwrap();
+    assert_eq!(again.len(), 1);
+}
+
+#[test]
+fn rename_emits_renamed() {
+    let mut asset = Asset::new(
+        AssetId(Uuid::new_v4()),
+        "drill".parse().unwrap(),
+        None::<String>,
+        1u32,
+    );
+    let name: Name = "Cordless drill".parse().unwrap();
+
+    let events = asset
+        .handle_command(AssetCommand::Rename { name: name.clone() })
+        .unwrap();
+    assert!(matches!(&events[..], [AssetEvent::Renamed { name: n }] if *n == name));
+    asset.apply_event(&events[0]).unwrap();
+    assert_eq!(asset.name, name);
+}
+
+#[test]
+fn receiving_always_succeeds() {
+    let mut asset = Asset::new(
+        AssetId(Uuid::new_v4()),
+        "tape".parse().unwrap(),
+        None::<String>,
+        u32::MAX - 1,
+    );
+
+    let events = asset
+        .handle_command(AssetCommand::Receive { n: 5 })
+        .unwrap();
+    assert!(matches!(&events[..], [AssetEvent::Received { n: 5 }]));
+    asset.apply_event(&events[0]).unwrap();
+    assert_eq!(asset.quantity.get(), u32::MAX);
+}
+
+#[test]
+fn issuing_more_than_on_hand_is_rejected() {
+    let mut asset = Asset::new(
+        AssetId(Uuid::new_v4()),
+        "tape".parse().unwrap(),
+        None::<String>,
+        3u32,
+    );
+
+    let events = asset.handle_command(AssetCommand::Issue { n: 2 }).unwrap();
+    assert!(matches!(&events[..], [AssetEvent::Issued { n: 2 }]));
+    asset.apply_event(&events[0]).unwrap();
+    assert_eq!(asset.quantity.get(), 1);
+
+    let err = asset
+        .handle_command(AssetCommand::Issue { n: 2 })
+        .unwrap_err();
+    assert_eq!(err, QuantityError::Negative.into());
+    assert_eq!(asset.quantity.get(), 1);
+}
+
+#[test]
+fn adjust_quantity_never_goes_below_zero() {
+    let mut asset = Asset::new(
+        AssetId(Uuid::new_v4()),
+        "drill".parse().unwrap(),
+        None::<String>,
+        1u32,
+    );
+
+    let events = asset
+        .handle_command(AssetCommand::AdjustQuantity { delta: 2 })
+        .unwrap();
+    assert!(matches!(
+        &events[..],
+        [AssetEvent::QuantityAdjusted { delta: 2 }]
+    ));
+    asset.apply_event(&events[0]).unwrap();
+    assert_eq!(asset.quantity.get(), 3);
+
+    let err = asset
+        .handle_command(AssetCommand::AdjustQuantity { delta: -4 })
+        .unwrap_err();
+    assert_eq!(err, QuantityError::Negative.into());
+    assert_eq!(asset.quantity.get(), 3);
 }
@@ -1,5 +1,199 @@
-#[derive(Debug, Clone)]
-pub struct User {
-    pub id: u64,
-    pub name: String,
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use jsonwebtoken::{errors::ErrorKind, DecodingKey, EncodingKey, Header, Validation};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use uuid::Uuid;
+
+use crate::error::AuthError;
+use crate::user::{Role, User, UserId};
+
+/// Hash `plain` with Argon2id and a fresh random salt, returning the PHC
+/// string to store in place of the password.
+pub fn hash_password(plain: &str) -> Result<String, AuthError> {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(plain.as_bytes(), &salt)
+        .map(|hash| hash.to_string())
+        .map_err(|e| AuthError::Hash(e.to_string()))
+}
+
+/// Whether `plain` matches a hash produced by [hash_password]. A malformed
+/// `hash` is an error; a wrong password is `Ok(false)`.
+pub fn verify_password(plain: &str, hash: &str) -> Result<bool, AuthError> {
+    let parsed = PasswordHash::new(hash).map_err(|e| AuthError::Hash(e.to_string()))?;
+    match Argon2::default().verify_password(plain.as_bytes(), &parsed) {
+        Ok(()) => Ok(true),
+        Err(argon2::password_hash::Error::Password) => Ok(false),
+        Err(e) => Err(AuthError::Hash(e.to_string())),
+    }
+}
+
+/// What a session token asserts: who it was issued to and until when.
+#[derive(Debug, Serialize, Deserialize)]
+struct Claims {
+    sub: Uuid,
+    iat: u64,
+    exp: u64,
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs())
+}
+
+/// Issue an HS256-signed session token for `user_id`, valid for `ttl`.
+pub fn issue_token(user_id: UserId, secret: &[u8], ttl: Duration) -> Result<String, AuthError> {
+    let now = unix_now();
+    encode_claims(
+        &Claims {
+            sub: user_id.into(),
+            iat: now,
+            exp: now.saturating_add(ttl.as_secs()),
+        },
+        secret,
+    )
+}
+
+fn encode_claims(claims: &Claims, secret: &[u8]) -> Result<String, AuthError> {
+    jsonwebtoken::encode(
+        &Header::default(),
+        claims,
+        &EncodingKey::from_secret(secret),
+    )
+    .map_err(|e| AuthError::InvalidToken(e.to_string()))
+}
+
+/// The user a token issued by [issue_token] with the same `secret` belongs
+/// to. Errors with [AuthError::TokenExpired] once it has expired and with
+/// [AuthError::InvalidToken] if it is malformed or its signature doesn't match.
+pub fn verify_token(token: &str, secret: &[u8]) -> Result<UserId, AuthError> {
+    let mut validation = Validation::default();
+    validation.leeway = 0;
+    let data =
+        jsonwebtoken::decode::<Claims>(token, &DecodingKey::from_secret(secret), &validation)
+            .map_err(|e| match e.kind() {
+                ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+                _ => AuthError::InvalidToken(e.to_string()),
+            })?;
+    Ok(UserId::from(data.claims.sub))
+}
+
+/// Something a [User] may attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Create,
+    Update,
+    Delete,
+    View,
+}
+
+/// Allow `action` if `user`'s [Role] permits it: viewers can only view,
+/// editors can do anything but delete, and admins can do everything.
+pub fn authorize(user: &User, action: Action) -> Result<(), AuthError> {
+    let allowed = match user.role {
+        Role::Admin => true,
+        Role::Editor => action != Action::Delete,
+        Role::Viewer => action == Action::View,
+    };
+    if !allowed {
+        return Err(AuthError::Forbidden {
+            role: user.role,
+            action,
+        });
+    }
+    Ok(())
+}
+
+#[test]
+fn hashed_password_verifies() {
+    let hash = hash_password("correct horse").unwrap();
+    assert!(!hash.contains("correct horse"));
+    assert!(verify_password("correct horse", &hash).unwrap());
+}
+
+#[test]
+fn wrong_password_fails() {
+    let hash = hash_password("correct horse").unwrap();
+    assert!(!verify_password("battery staple", &hash).unwrap());
+    assert!(verify_password("correct horse", "not a hash").is_err());
+}
+
+#[test]
+fn same_password_hashes_differently() {
+    let a = hash_password("correct horse").unwrap();
+    let b = hash_password("correct horse").unwrap();
+    assert_ne!(a, b);
+    assert!(verify_password("correct horse", &a).unwrap());
+    assert!(verify_password("correct horse", &b).unwrap());
+}
+
+#[test]
+fn token_round_trips() {
+    let user = UserId::new();
+    let token = issue_token(user.clone(), b"secret", Duration::from_secs(60)).unwrap();
+    assert_eq!(verify_token(&token, b"secret").unwrap(), user);
+}
+
+#[test]
+fn expired_token_is_rejected() {
+    let past = unix_now() - 3600;
+    let token = encode_claims(
+        &Claims {
+            sub: Uuid::new_v4(),
+            iat: past - 60,
+            exp: past,
+        },
+        b"secret",
+    )
+    .unwrap();
+    assert_eq!(
+        verify_token(&token, b"secret"),
+        Err(AuthError::TokenExpired)
+    );
+}
+
+#[test]
+fn token_from_another_secret_is_rejected() {
+    let token = issue_token(UserId::new(), b"secret", Duration::from_secs(60)).unwrap();
+    assert!(matches!(
+        verify_token(&token, b"other secret"),
+        Err(AuthError::InvalidToken(_))
+    ));
+
+    let mut tampered = token.clone();
+    tampered.insert(token.len() / 2, 'x');
+    assert!(matches!(
+        verify_token(&tampered, b"secret"),
+        Err(AuthError::InvalidToken(_))
+    ));
+}
+
+#[test]
+fn authorize_by_role() {
+    use Action::*;
+    use Role::*;
+
+    #[rustfmt::skip]
+    let table = [
+        (Admin,  Create, true),  (Admin,  Update, true),  (Admin,  Delete, true),  (Admin,  View, true),
+        (Editor, Create, true),  (Editor, Update, true),  (Editor, Delete, false), (Editor, View, true),
+        (Viewer, Create, false), (Viewer, Update, false), (Viewer, Delete, false), (Viewer, View, true),
+    ];
+    for (role, action, allowed) in table {
+        let user = User::new(UserId::new(), "u", "u@example.com", "", role);
+        let outcome = authorize(&user, action);
+        if allowed {
+            assert_eq!(outcome, Ok(()), "{role:?} should be allowed to {action:?}");
+        } else {
+            assert_eq!(
+                outcome,
+                Err(AuthError::Forbidden { role, action }),
+                "{role:?} should not be allowed to {action:?}"
+            );
+        }
+    }
 }
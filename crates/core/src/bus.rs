@@ -0,0 +1,181 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::common::{replay, Aggregate};
+use crate::error::RepositoryError;
+use crate::eventstore::EventStore;
+
+/// Runs commands against event-sourced aggregates: loads the aggregate from
+/// its [EventStore], lets it handle the command, and records the events it
+/// produced.
+pub struct CommandBus<A: Aggregate> {
+    store: Arc<EventStore<A>>,
+}
+
+impl<A: Aggregate> Clone for CommandBus<A> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+        }
+    }
+}
+
+impl<A> CommandBus<A>
+where
+    A: Aggregate + Default + Clone,
+    A::Event: Clone,
+    A::Error: From<anyhow::Error> + From<RepositoryError>,
+{
+    pub fn new(store: Arc<EventStore<A>>) -> Self {
+        Self { store }
+    }
+
+    /// The store commands are recorded in.
+    pub fn store(&self) -> &EventStore<A> {
+        &self.store
+    }
+
+    /// The aggregate as its latest snapshot and the events since leave it; an
+    /// aggregate with no history is `A::default()`.
+    pub async fn load(&self, aggregate_id: Uuid) -> Result<A, A::Error> {
+        self.load_versioned(aggregate_id)
+            .await
+            .map(|(aggregate, _)| aggregate)
+    }
+
+    /// [CommandBus::load], plus the version of the log it was built from.
+    async fn load_versioned(&self, aggregate_id: Uuid) -> Result<(A, u64), A::Error> {
+        let snapshot = self.store.load_snapshot(aggregate_id).await?;
+        let log = self.store.load(aggregate_id).await?;
+        let version = log.len() as u64;
+        let events: Vec<A::Event> = log.into_iter().map(|e| e.event).collect();
+        Ok((replay(snapshot, &events)?, version))
+    }
+
+    /// Run `cmd` against the aggregate and append the events it produced,
+    /// returning them. A rejected command, or an event the aggregate can't
+    /// apply, leaves the log untouched, as does another command's events
+    /// landing first (a [RepositoryError::Conflict], converted into
+    /// `A::Error`, e.g. [AggregateError::Conflict](crate::AggregateError::Conflict)).
+    pub async fn dispatch(
+        &self,
+        aggregate_id: Uuid,
+        cmd: A::Command,
+    ) -> Result<Vec<A::Event>, A::Error> {
+        let (aggregate, version) = self.load_versioned(aggregate_id).await?;
+        self.dispatch_at(aggregate_id, aggregate, version, cmd)
+            .await
+    }
+
+    /// [CommandBus::dispatch] against `aggregate` as it was at `version`.
+    async fn dispatch_at(
+        &self,
+        aggregate_id: Uuid,
+        mut aggregate: A,
+        version: u64,
+        cmd: A::Command,
+    ) -> Result<Vec<A::Event>, A::Error> {
+        let events = aggregate.handle_command(cmd)?;
+        for event in &events {
+            aggregate.apply_event(event)?;
+        }
+        self.store
+            .append(aggregate_id, version, events.clone())
+            .await?;
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{AggregateError, Repository, RepositoryId};
+    use serde::{Deserialize, Serialize};
+    use stowr_macro::{command, domain, domain_impl};
+
+    #[domain]
+    struct Shelf {
+        label: String,
+    }
+
+    #[domain_impl]
+    impl Shelf {
+        #[command(event = Renamed, validate = can_rename)]
+        fn rename(&mut self, label: String) -> Result<(), AggregateError> {
+            self.can_rename(&label)?;
+            self.label = label;
+            Ok(())
+        }
+
+        fn can_rename(&self, label: &str) -> Result<(), AggregateError> {
+            if label.trim().is_empty() {
+                return Err(AggregateError::InvariantViolated(
+                    "label must not be empty".into(),
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    impl Default for Shelf {
+        fn default() -> Self {
+            Shelf::new(ShelfId::new(), "unlabelled")
+        }
+    }
+
+    fn rename(label: &str) -> ShelfCommand {
+        ShelfCommand::Rename {
+            label: label.into(),
+        }
+    }
+
+    #[tokio::test]
+    async fn dispatch_records_events_and_updates_state() {
+        let bus = CommandBus::new(Arc::new(EventStore::<Shelf>::new()));
+        let id = Uuid::new_v4();
+
+        let events = bus.dispatch(id, rename("Garage")).await.unwrap();
+        assert!(matches!(&events[..], [ShelfEvent::Renamed { label }] if label == "Garage"));
+        bus.dispatch(id, rename("Garage, top")).await.unwrap();
+
+        assert_eq!(bus.load(id).await.unwrap().label, "Garage, top");
+        let log = bus.store().load(id).await.unwrap();
+        let sequences: Vec<u64> = log.iter().map(|e| e.sequence).collect();
+        assert_eq!(sequences, vec![1, 2]);
+        assert_eq!(bus.load(Uuid::new_v4()).await.unwrap().label, "unlabelled");
+    }
+
+    #[tokio::test]
+    async fn rejected_commands_record_nothing() {
+        let bus = CommandBus::new(Arc::new(EventStore::<Shelf>::new()));
+        let id = Uuid::new_v4();
+        bus.dispatch(id, rename("Garage")).await.unwrap();
+
+        assert!(matches!(
+            bus.dispatch(id, rename(" ")).await,
+            Err(AggregateError::InvariantViolated(_))
+        ));
+        assert_eq!(bus.store().load(id).await.unwrap().len(), 1);
+        assert_eq!(bus.load(id).await.unwrap().label, "Garage");
+    }
+
+    #[tokio::test]
+    async fn commands_against_a_stale_version_conflict() {
+        let bus = CommandBus::new(Arc::new(EventStore::<Shelf>::new()));
+        let id = Uuid::new_v4();
+        bus.dispatch(id, rename("Garage")).await.unwrap();
+        let (stale, version) = bus.load_versioned(id).await.unwrap();
+        bus.dispatch(id, rename("Garage, top")).await.unwrap();
+
+        assert_eq!(
+            bus.dispatch_at(id, stale, version, rename("Shed"))
+                .await
+                .unwrap_err(),
+            AggregateError::Conflict {
+                expected: 1,
+                actual: 2
+            }
+        );
+        assert_eq!(bus.load(id).await.unwrap().label, "Garage, top");
+    }
+}
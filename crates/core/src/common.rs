@@ -1,34 +1,280 @@
-use anyhow::Result;
 use async_trait::async_trait;
-use serde::{Deserialize, Serialize};
-use std::{fmt, marker::PhantomData, str::FromStr};
-use uuid::Uuid;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    str::FromStr,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+use uuid::{Uuid, Version};
+
+use crate::error::{IdParseError, QuantityError, RepositoryError};
+use crate::eventstore::Snapshot;
 
 // ANCHOR: repository
 /// Base trait for all domain repositories.
-/// - [Entity] is the domain type (e.g. [Asset], [Location]).  
-/// - [Id] is the type of its primary‐key (e.g. [AssetId], [LocationId]).
+/// - [Entity] is the domain type (e.g. [Asset](crate::Asset), [Location](crate::Location)).  
+/// - [Id] is the type of its primary‐key (e.g. [AssetId](crate::asset::AssetId), [LocationId](crate::location::LocationId)).
 #[async_trait]
 pub trait Repository {
-    /// The domain object (e.g. [Asset], [Location]).
+    /// The domain object (e.g. [Asset](crate::Asset), [Location](crate::Location)).
     type Entity: Send + Sync;
-    /// The ID type (e.g. [AssetId]).
+    /// The ID type (e.g. [AssetId](crate::asset::AssetId)).
     type Id: Send + Sync;
 
     /// Create a new [Entity] and return it (with its new `Id` set).
-    async fn create(&self, entity: Self::Entity) -> Result<Self::Entity>;
+    async fn create(&self, entity: Self::Entity) -> Result<Self::Entity, RepositoryError>;
+
+    /// Create several [Entity]s as one logical operation and return them.
+    async fn create_many(
+        &self,
+        entities: Vec<Self::Entity>,
+    ) -> Result<Vec<Self::Entity>, RepositoryError>;
 
     /// Fetch an [Entity] by its ID (or return `None` if not found).
-    async fn fetch(&self, id: Self::Id) -> Result<Option<Self::Entity>>;
+    async fn fetch(&self, id: Self::Id) -> Result<Option<Self::Entity>, RepositoryError>;
+
+    /// Fetch every [Entity] whose ID is in `ids`, in the order given.
+    /// IDs that are not found are skipped.
+    async fn fetch_many(&self, ids: &[Self::Id]) -> Result<Vec<Self::Entity>, RepositoryError>;
+
+    /// Check whether an [Entity] with the given ID exists.
+    ///
+    /// The default delegates to `fetch`; backends with a cheaper existence
+    /// query should override it.
+    async fn exists(&self, id: Self::Id) -> Result<bool, RepositoryError> {
+        self.fetch(id).await.map(|o| o.is_some())
+    }
+
+    /// Replace the stored [Entity] with the same `Id` and return it (or
+    /// return [RepositoryError::NotFound] if no such entity exists).
+    async fn update(&self, entity: Self::Entity) -> Result<Self::Entity, RepositoryError>;
+
+    /// Remove the [Entity] with the given ID (or return
+    /// [RepositoryError::NotFound] if no such entity exists). Deleting twice
+    /// is therefore an error; match `NotFound` to treat it as already done.
+    async fn delete(&self, id: Self::Id) -> Result<(), RepositoryError>;
+
+    /// Return every stored [Entity].
+    async fn list(&self) -> Result<Vec<Self::Entity>, RepositoryError>;
+
+    /// Return one [Page] of stored [Entity]s, in the same order as `list`.
+    async fn list_page(&self, page: Page) -> Result<Vec<Self::Entity>, RepositoryError>;
+
+    /// Number of stored [Entity]s. The default counts the result of `list`.
+    async fn count(&self) -> Result<usize, RepositoryError> {
+        self.list().await.map(|all| all.len())
+    }
+
+    /// Like `update`, but when another update got there first (a
+    /// [RepositoryError::Conflict]) the changes `entity` makes to `base`, the
+    /// [Entity] it was edited from, are merged into the stored [Entity] with
+    /// [MergeableEntity::merge] and that is stored instead, retrying until no
+    /// other update gets in between.
+    async fn update_merging(
+        &self,
+        base: &Self::Entity,
+        entity: Self::Entity,
+    ) -> Result<Self::Entity, RepositoryError>
+    where
+        Self::Entity: MergeableEntity + Identifiable + Clone,
+        Self::Id: From<Uuid>,
+    {
+        let mut candidate = entity.clone();
+        loop {
+            match self.update(candidate).await {
+                Err(RepositoryError::Conflict { .. }) => {
+                    let stored = self
+                        .fetch(entity.id().into())
+                        .await?
+                        .ok_or(RepositoryError::NotFound)?;
+                    // Merge the caller's entity afresh each time, so a
+                    // second conflict doesn't count its changes twice.
+                    candidate = stored.merge(base, &entity);
+                }
+                result => return result,
+            }
+        }
+    }
 }
 // ANCHOR_END: repository
 
+/// An offset/limit window over a repository listing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Page {
+    /// Number of entities to skip.
+    pub offset: usize,
+    /// Maximum number of entities to return.
+    pub limit: usize,
+}
+
+impl Page {
+    pub fn new(offset: usize, limit: usize) -> Self {
+        Self { offset, limit }
+    }
+
+    /// The part of `items` covered by this page, empty when `offset` is past
+    /// the end and clamped when `limit` runs past it.
+    pub fn slice<'a, T>(&self, items: &'a [T]) -> &'a [T] {
+        let start = self.offset.min(items.len());
+        let end = start.saturating_add(self.limit).min(items.len());
+        &items[start..end]
+    }
+}
+
+/// Entities that can report their own id (e.g. [Asset](crate::Asset),
+/// [Location](crate::Location)).
+pub trait Identifiable {
+    /// The entity's typed id, convertible to the underlying [Uuid].
+    type Id: Clone + Into<Uuid> + Send + Sync;
+
+    fn id(&self) -> Uuid;
+
+    /// Optimistic-locking version, for entities that keep one. Repositories
+    /// only accept an update whose version matches the stored entity's, and
+    /// store it with the version bumped.
+    fn version(&self) -> Option<u64> {
+        None
+    }
+
+    /// Overwrite the version; only repositories should call this.
+    fn set_version(&mut self, _version: u64) {}
+
+    /// When the entity was first stored, for entities that keep timestamps.
+    fn created_at(&self) -> Option<SystemTime> {
+        None
+    }
+
+    /// Record when the entity was created and last updated; only
+    /// repositories should call this.
+    fn set_timestamps(&mut self, _created_at: SystemTime, _updated_at: SystemTime) {}
+}
+
+/// The current time.
+///
+/// `SystemTime::now` panics on `wasm32-unknown-unknown`, where the GUI keeps
+/// its repositories, so the browser's clock is asked there instead.
+pub fn now() -> SystemTime {
+    #[cfg(target_arch = "wasm32")]
+    return UNIX_EPOCH + Duration::from_secs_f64(js_sys::Date::now() / 1000.0);
+    #[cfg(not(target_arch = "wasm32"))]
+    SystemTime::now()
+}
+
+/// What a missing timestamp deserializes to.
+pub(crate) fn unix_epoch() -> SystemTime {
+    UNIX_EPOCH
+}
+
+/// `#[serde(with = "rfc3339")]` for entity timestamps: an RFC 3339 string in
+/// UTC, e.g. `"2025-06-01T12:30:00.5Z"`. Reading also accepts serde's own
+/// `{"secs_since_epoch", "nanos_since_epoch"}` form, which entities were
+/// stored in before.
+pub(crate) mod rfc3339 {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+    use std::time::SystemTime;
+    use time::{format_description::well_known::Rfc3339, OffsetDateTime};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Stored {
+        Rfc3339(String),
+        Legacy(SystemTime),
+    }
+
+    pub fn serialize<S: Serializer>(at: &SystemTime, serializer: S) -> Result<S::Ok, S::Error> {
+        let formatted = OffsetDateTime::from(*at)
+            .format(&Rfc3339)
+            .map_err(serde::ser::Error::custom)?;
+        formatted.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<SystemTime, D::Error> {
+        match Stored::deserialize(deserializer)? {
+            Stored::Rfc3339(s) => OffsetDateTime::parse(&s, &Rfc3339)
+                .map(SystemTime::from)
+                .map_err(D::Error::custom),
+            Stored::Legacy(at) => Ok(at),
+        }
+    }
+
+    /// The same for an `Option<SystemTime>`, `None` being `null`.
+    pub mod option {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(
+            at: &Option<SystemTime>,
+            serializer: S,
+        ) -> Result<S::Ok, S::Error> {
+            match at {
+                Some(at) => super::serialize(at, serializer),
+                None => serializer.serialize_none(),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(
+            deserializer: D,
+        ) -> Result<Option<SystemTime>, D::Error> {
+            #[derive(Deserialize)]
+            struct Wrapped(#[serde(with = "super")] SystemTime);
+            Ok(Option::<Wrapped>::deserialize(deserializer)?.map(|Wrapped(at)| at))
+        }
+    }
+}
+
+/// Entities that expose a human-readable name (e.g. [Asset](crate::Asset),
+/// [Location](crate::Location)).
+pub trait Named {
+    fn name(&self) -> &str;
+
+    /// Exact, case-insensitive comparison against [Named::name].
+    fn name_matches(&self, name: &str) -> bool {
+        self.name().to_lowercase() == name.to_lowercase()
+    }
+}
+
+/// Entities that can be soft-deleted: marked with the time they were deleted
+/// rather than removed, so they can be restored (see
+/// [SoftDeleteRepository](crate::db::SoftDeleteRepository)).
+pub trait SoftDeletable {
+    /// When the entity was deleted, or `None` if it is live.
+    fn deleted_at(&self) -> Option<SystemTime>;
+
+    fn set_deleted_at(&mut self, at: Option<SystemTime>);
+
+    fn is_deleted(&self) -> bool {
+        self.deleted_at().is_some()
+    }
+}
+
+/// Entities whose concurrent updates can be combined instead of one of them
+/// being rejected (see [Repository::update_merging]).
+pub trait MergeableEntity {
+    /// Apply the changes `other` makes to `base` to `self`, the entity as
+    /// stored, where `base` is the entity both were edited from. The result
+    /// keeps `self`'s version so it can be stored over it.
+    fn merge(&self, base: &Self, other: &Self) -> Self;
+}
+
+/// Repositories whose [Entity] can be looked up by its [Named::name].
+#[async_trait]
+pub trait NamedRepository: Repository
+where
+    Self::Entity: Named,
+{
+    /// Fetch the [Entity] whose name matches exactly, ignoring case (or
+    /// return `None` if there is no match).
+    async fn find_by_name(&self, name: &str) -> Result<Option<Self::Entity>, RepositoryError>;
+}
+
 // ANCHOR: Repository_id
-/// The one-and-only underlying ID type, always a v4 UUID.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+/// The one-and-only underlying ID type: a v4 UUID, or a time-ordered v7 via
+/// [RepositoryId::new_v7].
+#[derive(Clone, Copy, Debug)]
 pub struct RepositoryId<T> {
     value: Uuid,
-    #[serde(skip)]
     _marker: PhantomData<T>,
 }
 // ANCHOR_END: Repository_id
@@ -47,6 +293,79 @@ impl<T> RepositoryId<T> {
             _marker: PhantomData,
         }
     }
+
+    /// Create a time-ordered v7 UUID, which keeps database indexes local
+    pub fn new_v7() -> Self {
+        Self {
+            value: Uuid::now_v7(),
+            _marker: PhantomData,
+        }
+    }
+
+    /// The all-zero id, a placeholder that must be replaced before the entity
+    /// is persisted
+    pub fn nil() -> Self {
+        Uuid::nil().into()
+    }
+
+    /// Whether this is the placeholder [RepositoryId::nil] id
+    pub fn is_nil(&self) -> bool {
+        self.value.is_nil()
+    }
+
+    /// Create a deterministic v5 UUID from a namespace and a name (e.g. a
+    /// SKU), so the same external key always maps to the same id
+    pub fn from_name(namespace: Uuid, name: &str) -> Self {
+        Uuid::new_v5(&namespace, name.as_bytes()).into()
+    }
+
+    /// The raw 16 bytes of the underlying UUID
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        self.value.as_bytes()
+    }
+
+    /// Rebuild an id from the raw 16 bytes produced by [RepositoryId::as_bytes]
+    pub fn from_bytes(b: [u8; 16]) -> Self {
+        Uuid::from_bytes(b).into()
+    }
+
+    /// The creation time embedded in a v7 id, or `None` for other versions
+    pub fn timestamp(&self) -> Option<SystemTime> {
+        if self.value.get_version() != Some(Version::SortRand) {
+            return None;
+        }
+        let (secs, nanos) = self.value.get_timestamp()?.to_unix();
+        Some(UNIX_EPOCH + Duration::new(secs, nanos))
+    }
+}
+
+// Comparison and hashing are implemented by hand so they only look at the
+// underlying UUID and don't require any bounds on the marker `T`.
+impl<T> PartialEq for RepositoryId<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value
+    }
+}
+
+impl<T> Eq for RepositoryId<T> {}
+
+impl<T> PartialOrd for RepositoryId<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Orders by the underlying UUID bytes.
+impl<T> Ord for RepositoryId<T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.value.cmp(&other.value)
+    }
+}
+
+impl<T> Hash for RepositoryId<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.value.hash(state);
+    }
 }
 
 impl<T> fmt::Display for RepositoryId<T> {
@@ -56,9 +375,9 @@ impl<T> fmt::Display for RepositoryId<T> {
 }
 
 impl<T> FromStr for RepositoryId<T> {
-    type Err = uuid::Error;
+    type Err = IdParseError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let u = Uuid::from_str(s)?;
+        let u = Uuid::from_str(s).map_err(IdParseError)?;
         Ok(RepositoryId {
             value: u,
             _marker: PhantomData,
@@ -66,6 +385,63 @@ impl<T> FromStr for RepositoryId<T> {
     }
 }
 
+/// Allows `let id = FooId::try_from("550e8400-...")?;`
+impl<T> TryFrom<&str> for RepositoryId<T> {
+    type Error = IdParseError;
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+/// Serializes as the plain UUID string, e.g. `"550e8400-e29b-41d4-a716-446655440000"`.
+impl<T> Serialize for RepositoryId<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+impl<'de, T> Deserialize<'de> for RepositoryId<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Uuid::deserialize(deserializer).map(Self::from)
+    }
+}
+
+/// Documented as a UUID string, which is how it is serialized.
+#[cfg(feature = "openapi")]
+impl<T> utoipa::PartialSchema for RepositoryId<T> {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{KnownFormat, ObjectBuilder, SchemaFormat, Type};
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .format(Some(SchemaFormat::KnownFormat(KnownFormat::Uuid)))
+            .into()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl<T> utoipa::ToSchema for RepositoryId<T> {}
+
+/// Any UUID, not just the versions [RepositoryId::new] and friends make.
+#[cfg(any(test, feature = "testing"))]
+impl<T: fmt::Debug + 'static> proptest::arbitrary::Arbitrary for RepositoryId<T> {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::prelude::*;
+        any::<[u8; 16]>().prop_map(Self::from_bytes).boxed()
+    }
+}
+
+/// A time with whole nanoseconds between the epoch and the year ~2100, for
+/// generating entity timestamps.
+#[cfg(any(test, feature = "testing"))]
+pub(crate) fn any_time() -> impl proptest::strategy::Strategy<Value = SystemTime> {
+    use proptest::prelude::*;
+    (0..4_102_444_800u64, 0..1_000_000_000u32)
+        .prop_map(|(secs, nanos)| UNIX_EPOCH + Duration::new(secs, nanos))
+}
+
 /// Allows `let id: Id<Foo> = uuid.into();`
 impl<T> From<Uuid> for RepositoryId<T> {
     fn from(value: Uuid) -> Self {
@@ -83,20 +459,114 @@ impl<T> From<RepositoryId<T>> for Uuid {
     }
 }
 
+/// Runtime description of a `#[domain]` type, registered at compile time so
+/// admin tooling can enumerate every domain without hard-coding the list.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DomainInfo {
+    /// The domain struct's name (e.g. `"Asset"`).
+    pub name: &'static str,
+    /// The struct's fields, starting with the generated `id`.
+    pub fields: &'static [FieldInfo],
+}
+
+/// Name and (stringified) type of a single [DomainInfo] field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub ty: &'static str,
+}
+
+inventory::collect!(DomainInfo);
+
+/// Every domain registered by `#[domain]`, sorted by name.
+pub fn registered_domains() -> Vec<DomainInfo> {
+    let mut domains: Vec<DomainInfo> = inventory::iter::<DomainInfo>.into_iter().copied().collect();
+    domains.sort_by_key(|d| d.name);
+    domains
+}
+
 /// in your `common.rs` (or wherever your macros live)
 pub trait Aggregate {
     type Command;
     type Event;
     type Error;
     fn handle_command(&self, cmd: Self::Command) -> Result<Vec<Self::Event>, Self::Error>;
-    fn apply_event(&mut self, evt: &Self::Event);
+    fn apply_event(&mut self, evt: &Self::Event) -> Result<(), Self::Error>;
+}
+
+/// Rebuild an aggregate by folding `events` over `snapshot`, or over
+/// `A::default()` when there is none.
+///
+/// `events` is the aggregate's full history; with a snapshot only the events
+/// after its version are applied. Use [from_events] to start from some other
+/// known state.
+pub fn replay<A: Aggregate + Default>(
+    snapshot: Option<Snapshot<A>>,
+    events: &[A::Event],
+) -> Result<A, A::Error> {
+    match snapshot {
+        Some(Snapshot { version, state }) => {
+            let rest = events.get(version as usize..).unwrap_or_default();
+            from_events(state, rest)
+        }
+        None => from_events(A::default(), events),
+    }
+}
+
+/// Apply `events` in order to `initial` and return the resulting aggregate.
+pub fn from_events<A: Aggregate>(mut initial: A, events: &[A::Event]) -> Result<A, A::Error> {
+    for evt in events {
+        initial.apply_event(evt)?;
+    }
+    Ok(initial)
+}
+
+/// Why a command was rejected or an event could not be applied.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+pub enum AggregateError {
+    #[error("aggregate not found")]
+    NotFound,
+    #[error("invariant violated: {0}")]
+    InvariantViolated(String),
+    #[error("version conflict: expected {expected}, found {actual}")]
+    Conflict { expected: u64, actual: u64 },
+    /// The event store failed to load or record events.
+    #[error("event store error: {0}")]
+    Store(String),
+}
+
+impl From<anyhow::Error> for AggregateError {
+    fn from(err: anyhow::Error) -> Self {
+        AggregateError::Store(err.to_string())
+    }
 }
 
-pub enum AggregateError {}
+/// A version conflict or a missing aggregate keeps its meaning; any other
+/// failure becomes [AggregateError::Store].
+impl From<RepositoryError> for AggregateError {
+    fn from(err: RepositoryError) -> Self {
+        match err {
+            RepositoryError::Conflict { expected, actual } => {
+                AggregateError::Conflict { expected, actual }
+            }
+            RepositoryError::NotFound => AggregateError::NotFound,
+            other => AggregateError::Store(other.to_string()),
+        }
+    }
+}
+
+impl From<QuantityError> for AggregateError {
+    fn from(err: QuantityError) -> Self {
+        AggregateError::InvariantViolated(err.to_string())
+    }
+}
 
 #[cfg(test)]
 mod tests {
-    use std::sync::{Arc, Mutex};
+    use std::{
+        collections::BTreeSet,
+        sync::{Arc, Mutex},
+    };
 
     use super::*;
     use crate::common::Repository;
@@ -104,21 +574,25 @@ mod tests {
 
     // ANCHOR: foo_domain
     /// The `#[domain]` attribute will expand [Foo] to have a [RepositoryId]
-    /// and `new()` impl with struct field declared as `impl Into<T>`.
+    /// and `new()` impl with the id and struct fields declared as `impl Into<T>`.
     /// Additionally it will create a trait object with blanket impl and a
     /// type alias to be used by users of concrete Repository implementations.
     ///
-    ///    #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+    ///    #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
     ///    enum FooTag {}
     ///    type FooId = RepositoryId<FooTag>;
     ///
     ///    impl Foo {
-    ///        fn new(id: FooId, name: impl Into<String>) -> Foo {
+    ///        fn new(id: impl Into<FooId>, name: impl Into<String>) -> Foo {
     ///            Foo {
-    ///                id,
+    ///                id: id.into(),
     ///                name: name.into(),
     ///            }
     ///        }
+    ///
+    ///        fn new_from_uuid(uuid: Uuid, name: impl Into<String>) -> Foo {
+    ///            Self::new(FooId::from(uuid), name)
+    ///        }
     ///    }
     ///
     ///    trait FooRepository: Repository<Entity = Foo, Id = FooId> + Send + Sync {}
@@ -138,6 +612,12 @@ mod tests {
             self.name = new_name;
         }
     }
+
+    impl Named for Foo {
+        fn name(&self) -> &str {
+            &self.name
+        }
+    }
     // ANCHOR_END: foo_domain
 
     #[test]
@@ -149,16 +629,85 @@ mod tests {
         assert_eq!(f.name, new_name);
     }
 
+    #[test]
+    fn rename_event_applies_cleanly() {
+        let mut f = Foo::new(FooId::new(), "Old Name");
+        let events = f
+            .handle_command(FooCommand::Rename {
+                new_name: "New Name".to_string(),
+            })
+            .unwrap();
+        for evt in &events {
+            assert!(f.apply_event(evt).is_ok());
+        }
+        assert_eq!(f.name, "New Name");
+    }
+
+    impl Default for Foo {
+        fn default() -> Self {
+            Foo::new(FooId::new(), "")
+        }
+    }
+
+    fn renames(names: &[&str]) -> Vec<FooEvent> {
+        names
+            .iter()
+            .map(|n| FooEvent::Rename {
+                new_name: n.to_string(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn aggregate_errors_display() {
+        assert_eq!(AggregateError::NotFound.to_string(), "aggregate not found");
+        assert_eq!(
+            AggregateError::InvariantViolated("name must not be empty".into()).to_string(),
+            "invariant violated: name must not be empty"
+        );
+        assert_eq!(
+            AggregateError::Conflict {
+                expected: 3,
+                actual: 5
+            }
+            .to_string(),
+            "version conflict: expected 3, found 5"
+        );
+        assert_eq!(
+            AggregateError::from(anyhow::anyhow!("disk full")).to_string(),
+            "event store error: disk full"
+        );
+        let err: Box<dyn std::error::Error> = Box::new(AggregateError::NotFound);
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn replay_folds_rename_events() {
+        let foo: Foo = replay(None, &renames(&["first", "second", "third"])).unwrap();
+        assert_eq!(foo.name, "third");
+    }
+
+    #[test]
+    fn from_events_starts_from_given_state() {
+        let id = FooId::new();
+        let initial = Foo::new(id.clone(), "initial");
+        let foo = from_events(initial.clone(), &[]).unwrap();
+        assert_eq!(foo, initial);
+
+        let foo = from_events(initial, &renames(&["renamed"])).unwrap();
+        assert_eq!(foo, Foo::new(id, "renamed"));
+    }
+
     // ANCHOR: vector_foo_repo
     struct VectorFooRepo {
         db: Mutex<Vec<Foo>>,
     }
 
     impl VectorFooRepo {
-        fn new() -> FooRepo {
-            Arc::new(Self {
+        fn new() -> Self {
+            Self {
                 db: Default::default(),
-            })
+            }
         }
     }
 
@@ -167,22 +716,74 @@ mod tests {
         type Entity = Foo;
         type Id = FooId;
 
-        async fn create(&self, entity: Foo) -> Result<Foo> {
+        async fn create(&self, entity: Foo) -> Result<Foo, RepositoryError> {
             let mut guard = self.db.lock().unwrap();
             guard.push(entity.clone());
             Ok(entity)
         }
 
-        async fn fetch(&self, id: FooId) -> Result<Option<Foo>> {
+        async fn create_many(&self, entities: Vec<Foo>) -> Result<Vec<Foo>, RepositoryError> {
+            let mut guard = self.db.lock().unwrap();
+            guard.extend(entities.iter().cloned());
+            Ok(entities)
+        }
+
+        async fn fetch(&self, id: FooId) -> Result<Option<Foo>, RepositoryError> {
+            let guard = self.db.lock().unwrap();
+            Ok(guard.iter().find(|d| d.id == id).cloned())
+        }
+
+        async fn fetch_many(&self, ids: &[FooId]) -> Result<Vec<Foo>, RepositoryError> {
+            let guard = self.db.lock().unwrap();
+            Ok(ids
+                .iter()
+                .filter_map(|id| guard.iter().find(|d| &d.id == id).cloned())
+                .collect())
+        }
+
+        async fn update(&self, entity: Foo) -> Result<Foo, RepositoryError> {
+            let mut guard = self.db.lock().unwrap();
+            let slot = guard
+                .iter_mut()
+                .find(|d| d.id == entity.id)
+                .ok_or(RepositoryError::NotFound)?;
+            *slot = entity.clone();
+            Ok(entity)
+        }
+
+        async fn delete(&self, id: FooId) -> Result<(), RepositoryError> {
+            let mut guard = self.db.lock().unwrap();
+            let before = guard.len();
+            guard.retain(|d| d.id != id);
+            if guard.len() == before {
+                return Err(RepositoryError::NotFound);
+            }
+            Ok(())
+        }
+
+        async fn list(&self) -> Result<Vec<Foo>, RepositoryError> {
+            let guard = self.db.lock().unwrap();
+            Ok(guard.clone())
+        }
+
+        async fn list_page(&self, page: Page) -> Result<Vec<Foo>, RepositoryError> {
+            let guard = self.db.lock().unwrap();
+            Ok(page.slice(&guard).to_vec())
+        }
+    }
+
+    #[async_trait]
+    impl NamedRepository for VectorFooRepo {
+        async fn find_by_name(&self, name: &str) -> Result<Option<Foo>, RepositoryError> {
             let guard = self.db.lock().unwrap();
-            Ok(guard.iter().cloned().find(|d| d.id == id))
+            Ok(guard.iter().find(|d| d.name_matches(name)).cloned())
         }
     }
     // ANCHOR_END: vector_foo_repo
 
     #[tokio::test]
     async fn dummy_repo_can_create_and_fetch() {
-        let repo = VectorFooRepo::new();
+        let repo: FooRepo = Arc::new(VectorFooRepo::new());
         let id = FooId::new();
         let item = Foo::new(id.clone(), "warehouse");
         let created = repo.create(item.clone()).await.unwrap();
@@ -192,6 +793,149 @@ mod tests {
         assert_eq!(fetched, Some(item));
     }
 
+    #[tokio::test]
+    async fn update_replaces_stored_entity() {
+        let repo = VectorFooRepo::new();
+        let mut item = Foo::new(FooId::new(), "warehouse");
+        repo.create(item.clone()).await.unwrap();
+
+        item.name = "garage".to_string();
+        let updated = repo.update(item.clone()).await.unwrap();
+        assert_eq!(updated, item);
+
+        let fetched = repo.fetch(item.id.clone()).await.unwrap();
+        assert_eq!(fetched, Some(item));
+    }
+
+    #[tokio::test]
+    async fn update_missing_id_is_not_found() {
+        let repo = VectorFooRepo::new();
+        let item = Foo::new(FooId::new(), "warehouse");
+        assert!(matches!(
+            repo.update(item).await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn delete_missing_id_is_not_found() {
+        let repo = VectorFooRepo::new();
+        let id = FooId::new();
+        repo.create(Foo::new(id.clone(), "warehouse"))
+            .await
+            .unwrap();
+
+        repo.delete(id.clone()).await.unwrap();
+        assert!(matches!(
+            repo.delete(id.clone()).await,
+            Err(RepositoryError::NotFound)
+        ));
+        assert_eq!(repo.fetch(id).await.unwrap(), None);
+    }
+
+    #[test]
+    fn anyhow_errors_become_backend_errors() {
+        let err: RepositoryError = anyhow::anyhow!("connection refused").into();
+        assert!(matches!(&err, RepositoryError::Backend(msg) if msg == "connection refused"));
+        assert_eq!(err.to_string(), "backend error: connection refused");
+    }
+
+    #[tokio::test]
+    async fn list_returns_entities_in_creation_order() {
+        let repo = VectorFooRepo::new();
+        let items: Vec<Foo> = ["a", "b", "c"]
+            .into_iter()
+            .map(|name| Foo::new(FooId::new(), name))
+            .collect();
+        for item in &items {
+            repo.create(item.clone()).await.unwrap();
+        }
+
+        assert_eq!(repo.list().await.unwrap(), items);
+    }
+
+    async fn repo_with(names: &[&str]) -> (VectorFooRepo, Vec<Foo>) {
+        let repo = VectorFooRepo::new();
+        let mut items = Vec::new();
+        for name in names {
+            items.push(repo.create(Foo::new(FooId::new(), *name)).await.unwrap());
+        }
+        (repo, items)
+    }
+
+    #[tokio::test]
+    async fn list_page_returns_window() {
+        let (repo, items) = repo_with(&["a", "b", "c", "d"]).await;
+        let page = repo.list_page(Page::new(1, 2)).await.unwrap();
+        assert_eq!(page, items[1..3]);
+
+        let clamped = repo.list_page(Page::new(3, 10)).await.unwrap();
+        assert_eq!(clamped, items[3..]);
+    }
+
+    #[tokio::test]
+    async fn list_page_past_end_is_empty() {
+        let (repo, _) = repo_with(&["a", "b"]).await;
+        let page = repo.list_page(Page::new(5, 10)).await.unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[tokio::test]
+    async fn list_page_with_zero_limit_is_empty() {
+        let (repo, _) = repo_with(&["a", "b"]).await;
+        let page = repo.list_page(Page::new(0, 0)).await.unwrap();
+        assert!(page.is_empty());
+    }
+
+    #[tokio::test]
+    async fn create_many_persists_all() {
+        let repo = VectorFooRepo::new();
+        let items: Vec<Foo> = (0..5)
+            .map(|i| Foo::new(FooId::new(), format!("foo {i}")))
+            .collect();
+        let created = repo.create_many(items.clone()).await.unwrap();
+        assert_eq!(created, items);
+
+        assert_eq!(repo.count().await.unwrap(), 5);
+        assert_eq!(repo.list().await.unwrap(), items);
+    }
+
+    #[tokio::test]
+    async fn fetch_many_skips_missing_and_keeps_order() {
+        let (repo, items) = repo_with(&["a", "b", "c"]).await;
+        let ids = [items[2].id.clone(), FooId::new(), items[0].id.clone()];
+        let fetched = repo.fetch_many(&ids).await.unwrap();
+        assert_eq!(fetched, vec![items[2].clone(), items[0].clone()]);
+    }
+
+    #[tokio::test]
+    async fn exists_reports_presence() {
+        let (repo, items) = repo_with(&["a"]).await;
+        assert!(repo.exists(items[0].id.clone()).await.unwrap());
+        assert!(!repo.exists(FooId::new()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn find_by_name_ignores_case() {
+        let repo = VectorFooRepo::new();
+        let item = Foo::new(FooId::new(), "Warehouse");
+        repo.create(item.clone()).await.unwrap();
+
+        let found = repo.find_by_name("wAREHOUSE").await.unwrap();
+        assert_eq!(found, Some(item));
+    }
+
+    #[tokio::test]
+    async fn find_by_name_misses_partial_names() {
+        let repo = VectorFooRepo::new();
+        repo.create(Foo::new(FooId::new(), "Warehouse"))
+            .await
+            .unwrap();
+
+        let found = repo.find_by_name("ware").await.unwrap();
+        assert_eq!(found, None);
+    }
+
     #[test]
     fn dummy_new_create_dummies() {
         let id = FooId::new();
@@ -216,6 +960,147 @@ mod tests {
         assert_eq!(original, reconstructed);
     }
 
+    #[test]
+    fn id_serializes_as_plain_uuid_string() {
+        let id = FooId::new();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, format!("\"{id}\""));
+
+        let parsed: FooId = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, id);
+    }
+
+    #[test]
+    fn id_vec_serializes_as_string_array() {
+        let ids = vec![FooId::new(), FooId::new()];
+        let json = serde_json::to_value(&ids).unwrap();
+        let expected: Vec<_> = ids.iter().map(|id| id.to_string()).collect();
+        assert_eq!(json, serde_json::json!(expected));
+    }
+
+    #[test]
+    fn v7_ids_sort_in_creation_order() {
+        let first: Uuid = FooId::new_v7().into();
+        let second: Uuid = FooId::new_v7().into();
+        assert!(first < second);
+    }
+
+    #[test]
+    fn timestamp_only_for_v7_ids() {
+        let before = SystemTime::now() - Duration::from_secs(1);
+        let stamp = FooId::new_v7().timestamp().expect("v7 ids carry a time");
+        assert!(stamp >= before && stamp <= SystemTime::now());
+
+        assert_eq!(FooId::new().timestamp(), None);
+    }
+
+    #[test]
+    fn ids_order_by_uuid_bytes() {
+        let ids: Vec<FooId> = (0..8).map(|_| FooId::new()).collect();
+        let set: BTreeSet<FooId> = ids.iter().cloned().collect();
+
+        let mut bytes: Vec<[u8; 16]> = ids.iter().map(|id| *id.as_bytes()).collect();
+        bytes.sort();
+        let ordered: Vec<[u8; 16]> = set.into_iter().map(|id| *id.as_bytes()).collect();
+        assert_eq!(ordered, bytes);
+    }
+
+    #[test]
+    fn from_name_is_deterministic() {
+        let a = FooId::from_name(Uuid::NAMESPACE_OID, "SKU-1");
+        let b = FooId::from_name(Uuid::NAMESPACE_OID, "SKU-1");
+        let c = FooId::from_name(Uuid::NAMESPACE_OID, "SKU-2");
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+    }
+
+    #[test]
+    fn nil_is_only_the_placeholder() {
+        assert!(FooId::nil().is_nil());
+        assert_eq!(FooId::nil().to_string(), Uuid::nil().to_string());
+        assert!(!FooId::new().is_nil());
+    }
+
+    #[test]
+    fn try_from_str_accepts_valid_uuid() {
+        let original = FooId::new();
+        let parsed = FooId::try_from(original.to_string().as_str()).unwrap();
+        assert_eq!(parsed, original);
+    }
+
+    #[test]
+    fn try_from_str_rejects_garbage() {
+        let err = FooId::try_from("not-a-uuid").unwrap_err();
+        assert!(err.to_string().starts_with("invalid id"));
+    }
+
+    #[test]
+    fn bytes_roundtrip_matches_string_roundtrip() {
+        let original = FooId::new();
+        let from_bytes = FooId::from_bytes(*original.as_bytes());
+        let from_str = FooId::from_str(&original.to_string()).unwrap();
+        assert_eq!(from_bytes, original);
+        assert_eq!(from_bytes, from_str);
+    }
+
+    #[test]
+    fn domain_macro_registers_foo() {
+        let foo = registered_domains()
+            .into_iter()
+            .find(|d| d.name == "Foo")
+            .expect("Foo should be registered by #[domain]");
+        let fields: Vec<_> = foo.fields.iter().map(|f| (f.name, f.ty)).collect();
+        assert_eq!(fields, vec![("id", "FooId"), ("name", "String")]);
+    }
+
+    #[test]
+    fn registry_lists_the_core_domains() {
+        let names: Vec<_> = registered_domains().iter().map(|d| d.name).collect();
+        for domain in ["Asset", "Group", "Location", "Tag", "User"] {
+            assert!(names.contains(&domain), "{domain} missing from {names:?}");
+        }
+        let location = registered_domains()
+            .into_iter()
+            .find(|d| d.name == "Location")
+            .unwrap();
+        assert_eq!(
+            location.fields[0],
+            FieldInfo {
+                name: "id",
+                ty: "LocationId"
+            }
+        );
+    }
+
+    /// Asset and Location list their fields by hand, so check the lists
+    /// against what they serialize, every optional field set.
+    #[test]
+    fn hand_registered_fields_match_the_serialized_ones() {
+        use crate::{asset::AssetId, location::LocationId, Asset, Location};
+        fn registered(domain: &str) -> BTreeSet<String> {
+            let info = registered_domains()
+                .into_iter()
+                .find(|d| d.name == domain)
+                .unwrap();
+            info.fields.iter().map(|f| f.name.to_string()).collect()
+        }
+        fn serialized(entity: impl serde::Serialize) -> BTreeSet<String> {
+            let value = serde_json::to_value(entity).unwrap();
+            value.as_object().unwrap().keys().cloned().collect()
+        }
+
+        let name: crate::validation::Name = "Drill".parse().unwrap();
+        let mut asset = Asset::new(AssetId(Uuid::now_v7()), name.clone(), Some("18V"), 1u32);
+        asset.location_id = Some(LocationId::new());
+        asset.deleted_at = Some(SystemTime::now());
+        assert_eq!(registered("Asset"), serialized(asset));
+
+        let mut location = Location::new(LocationId::new(), name, Some("Garage"));
+        location.parent_id = Some(LocationId::new());
+        location.deleted_at = Some(SystemTime::now());
+        assert_eq!(registered("Location"), serialized(location));
+    }
+
     #[test]
     fn parse_from_string_roundtrip() {
         let original = FooId::new();
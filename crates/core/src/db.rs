@@ -0,0 +1,1805 @@
+use async_trait::async_trait;
+use std::{
+    collections::{BTreeSet, HashMap},
+    future::Future,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant, UNIX_EPOCH},
+};
+use tracing::{debug, field, info_span, Instrument};
+use uuid::Uuid;
+
+use crate::{
+    asset::{Asset, AssetId, AssetQueries, Query},
+    common::now,
+    error::RepositoryError,
+    location::{Location, LocationId, LocationRepository},
+    tag::{TagId, TaggingRepository},
+    Identifiable, Named, NamedRepository, Page, Repository, SoftDeletable,
+};
+
+#[cfg(feature = "sqlite")]
+pub use sqlite::{SqliteEntity, SqliteEventLog, SqliteRepository};
+
+/// A [Repository] keeping entities in memory, keyed by [Identifiable::id].
+///
+/// Listings are in insertion order whatever kind of ids the entities carry;
+/// an update keeps the entity's place.
+pub struct InMemoryRepository<E> {
+    /// Each entity with the insertion number it lists by.
+    db: Mutex<HashMap<Uuid, (u64, E)>>,
+    inserted: AtomicU64,
+}
+
+impl<E> InMemoryRepository<E> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<E> Default for InMemoryRepository<E> {
+    fn default() -> Self {
+        Self {
+            db: Default::default(),
+            inserted: AtomicU64::new(0),
+        }
+    }
+}
+
+impl<E: Clone> InMemoryRepository<E> {
+    fn sorted(&self) -> Vec<E> {
+        let guard = self.db.lock().unwrap();
+        let mut entries: Vec<_> = guard.values().collect();
+        entries.sort_by_key(|(n, _)| *n);
+        entries.into_iter().map(|(_, e)| e.clone()).collect()
+    }
+
+    /// The next insertion number; only called with `db` locked, so numbers
+    /// follow the order entities are stored in.
+    fn next_insertion(&self) -> u64 {
+        self.inserted.fetch_add(1, Ordering::Relaxed)
+    }
+}
+
+#[async_trait]
+impl<E> Repository for InMemoryRepository<E>
+where
+    E: Identifiable + Clone + Send + Sync,
+{
+    type Entity = E;
+    type Id = E::Id;
+
+    async fn create(&self, mut entity: E) -> Result<E, RepositoryError> {
+        let mut guard = self.db.lock().unwrap();
+        if guard.contains_key(&entity.id()) {
+            return Err(RepositoryError::AlreadyExists);
+        }
+        stamp_created(&mut entity);
+        guard.insert(entity.id(), (self.next_insertion(), entity.clone()));
+        Ok(entity)
+    }
+
+    async fn create_many(&self, mut entities: Vec<E>) -> Result<Vec<E>, RepositoryError> {
+        let mut guard = self.db.lock().unwrap();
+        if entities.iter().any(|e| guard.contains_key(&e.id())) {
+            return Err(RepositoryError::AlreadyExists);
+        }
+        entities.iter_mut().for_each(stamp_created);
+        guard.extend(
+            entities
+                .iter()
+                .map(|e| (e.id(), (self.next_insertion(), e.clone()))),
+        );
+        Ok(entities)
+    }
+
+    async fn fetch(&self, id: E::Id) -> Result<Option<E>, RepositoryError> {
+        let guard = self.db.lock().unwrap();
+        Ok(guard.get(&id.into()).map(|(_, e)| e.clone()))
+    }
+
+    async fn fetch_many(&self, ids: &[E::Id]) -> Result<Vec<E>, RepositoryError> {
+        let guard = self.db.lock().unwrap();
+        Ok(ids
+            .iter()
+            .filter_map(|id| guard.get(&id.clone().into()).map(|(_, e)| e.clone()))
+            .collect())
+    }
+
+    async fn update(&self, mut entity: E) -> Result<E, RepositoryError> {
+        let mut guard = self.db.lock().unwrap();
+        let (_, slot) = guard
+            .get_mut(&entity.id())
+            .ok_or(RepositoryError::NotFound)?;
+        bump_version(&mut entity, slot.version())?;
+        let now = now();
+        entity.set_timestamps(slot.created_at().unwrap_or(now), now);
+        *slot = entity.clone();
+        Ok(entity)
+    }
+
+    async fn delete(&self, id: E::Id) -> Result<(), RepositoryError> {
+        let mut guard = self.db.lock().unwrap();
+        guard
+            .remove(&id.into())
+            .map(|_| ())
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn list(&self) -> Result<Vec<E>, RepositoryError> {
+        Ok(self.sorted())
+    }
+
+    async fn list_page(&self, page: Page) -> Result<Vec<E>, RepositoryError> {
+        Ok(page.slice(&self.sorted()).to_vec())
+    }
+
+    async fn count(&self) -> Result<usize, RepositoryError> {
+        Ok(self.db.lock().unwrap().len())
+    }
+}
+
+/// Stamp a new entity's creation and update times with now, unless it was
+/// stored once before and carries them already (e.g. restored from a backup).
+fn stamp_created<E: Identifiable>(entity: &mut E) {
+    if entity.created_at() == Some(UNIX_EPOCH) {
+        let now = now();
+        entity.set_timestamps(now, now);
+    }
+}
+
+/// Check an update to `entity` against the stored entity's version and bump
+/// it, for entities that are versioned.
+fn bump_version<E: Identifiable>(
+    entity: &mut E,
+    stored: Option<u64>,
+) -> Result<(), RepositoryError> {
+    if let (Some(expected), Some(actual)) = (entity.version(), stored) {
+        if expected != actual {
+            return Err(RepositoryError::Conflict { expected, actual });
+        }
+        entity.set_version(actual + 1);
+    }
+    Ok(())
+}
+
+#[async_trait]
+impl<E> NamedRepository for InMemoryRepository<E>
+where
+    E: Identifiable + Named + Clone + Send + Sync,
+{
+    async fn find_by_name(&self, name: &str) -> Result<Option<E>, RepositoryError> {
+        let guard = self.db.lock().unwrap();
+        Ok(guard
+            .values()
+            .map(|(_, e)| e)
+            .find(|e| e.name_matches(name))
+            .cloned())
+    }
+}
+
+#[async_trait]
+impl AssetQueries for InMemoryRepository<Asset> {
+    async fn by_location(&self, loc: LocationId) -> Result<Vec<Asset>, RepositoryError> {
+        Ok(self
+            .sorted()
+            .into_iter()
+            .filter(|a| a.location_id.as_ref() == Some(&loc))
+            .collect())
+    }
+
+    async fn search(&self, q: &str) -> Result<Vec<Asset>, RepositoryError> {
+        Ok(self.sorted().into_iter().filter(|a| a.matches(q)).collect())
+    }
+
+    async fn find(&self, q: Query) -> Result<Vec<Asset>, RepositoryError> {
+        Ok(self.sorted().into_iter().filter(|a| q.matches(a)).collect())
+    }
+}
+
+impl LocationRepository for InMemoryRepository<Location> {}
+
+/// A [TaggingRepository] keeping `(asset, tag)` pairs in memory.
+#[derive(Default)]
+pub struct InMemoryTagging {
+    pairs: Mutex<BTreeSet<(Uuid, Uuid)>>,
+}
+
+impl InMemoryTagging {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl TaggingRepository for InMemoryTagging {
+    async fn tag(&self, asset: AssetId, tag: TagId) -> Result<(), RepositoryError> {
+        self.pairs
+            .lock()
+            .unwrap()
+            .insert((asset.into(), tag.into()));
+        Ok(())
+    }
+
+    async fn untag(&self, asset: AssetId, tag: TagId) -> Result<(), RepositoryError> {
+        let removed = self
+            .pairs
+            .lock()
+            .unwrap()
+            .remove(&(asset.into(), tag.into()));
+        if !removed {
+            return Err(RepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn tags_of(&self, asset: AssetId) -> Result<Vec<TagId>, RepositoryError> {
+        let guard = self.pairs.lock().unwrap();
+        Ok(guard
+            .iter()
+            .filter(|(a, _)| *a == asset.0)
+            .map(|(_, t)| TagId::from(*t))
+            .collect())
+    }
+
+    async fn assets_with_tag(&self, tag: TagId) -> Result<Vec<AssetId>, RepositoryError> {
+        let tag: Uuid = tag.into();
+        let guard = self.pairs.lock().unwrap();
+        Ok(guard
+            .iter()
+            .filter(|(_, t)| *t == tag)
+            .map(|(a, _)| AssetId(*a))
+            .collect())
+    }
+}
+
+/// A [Repository] decorator that memoizes `fetch` results of the wrapped
+/// repository, evicting them once the entity has been updated or deleted.
+pub struct CachingRepository<R: Repository> {
+    inner: R,
+    cache: Mutex<HashMap<Uuid, R::Entity>>,
+    /// Bumped, with `cache` locked, after every write; a fetch only caches
+    /// what it read if no write finished in the meantime.
+    writes: AtomicU64,
+}
+
+impl<R: Repository> CachingRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            cache: Default::default(),
+            writes: AtomicU64::new(0),
+        }
+    }
+
+    /// The wrapped repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    /// Forget `key` after a write to it. Called once the write has returned,
+    /// failed or not, so a fetch racing the write can't cache what it
+    /// replaced.
+    fn evict(&self, key: Uuid) {
+        let mut cache = self.cache.lock().unwrap();
+        self.writes.fetch_add(1, Ordering::Relaxed);
+        cache.remove(&key);
+    }
+}
+
+#[async_trait]
+impl<R> Repository for CachingRepository<R>
+where
+    R: Repository + Send + Sync,
+    R::Entity: Identifiable<Id = R::Id> + Clone,
+    R::Id: Clone + Into<Uuid>,
+{
+    type Entity = R::Entity;
+    type Id = R::Id;
+
+    async fn create(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        self.inner.create(entity).await
+    }
+
+    async fn create_many(
+        &self,
+        entities: Vec<R::Entity>,
+    ) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.create_many(entities).await
+    }
+
+    async fn fetch(&self, id: R::Id) -> Result<Option<R::Entity>, RepositoryError> {
+        let key: Uuid = id.clone().into();
+        let writes = {
+            let cache = self.cache.lock().unwrap();
+            if let Some(hit) = cache.get(&key) {
+                return Ok(Some(hit.clone()));
+            }
+            self.writes.load(Ordering::Relaxed)
+        };
+        let fetched = self.inner.fetch(id).await?;
+        if let Some(entity) = &fetched {
+            let mut cache = self.cache.lock().unwrap();
+            if self.writes.load(Ordering::Relaxed) == writes {
+                cache.insert(key, entity.clone());
+            }
+        }
+        Ok(fetched)
+    }
+
+    async fn fetch_many(&self, ids: &[R::Id]) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.fetch_many(ids).await
+    }
+
+    async fn update(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        let key = entity.id();
+        let updated = self.inner.update(entity).await;
+        self.evict(key);
+        updated
+    }
+
+    async fn delete(&self, id: R::Id) -> Result<(), RepositoryError> {
+        let key = id.clone().into();
+        let deleted = self.inner.delete(id).await;
+        self.evict(key);
+        deleted
+    }
+
+    async fn list(&self) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.list().await
+    }
+
+    async fn list_page(&self, page: Page) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.list_page(page).await
+    }
+
+    async fn count(&self) -> Result<usize, RepositoryError> {
+        self.inner.count().await
+    }
+}
+
+/// A [Repository] decorator that runs each call of the wrapped repository in
+/// a `repository` span carrying the operation and entity id, and logs how long
+/// it took at debug level.
+pub struct TracingRepository<R> {
+    inner: R,
+}
+
+impl<R> TracingRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+async fn traced<T>(
+    op: &'static str,
+    id: Option<Uuid>,
+    call: impl Future<Output = Result<T, RepositoryError>>,
+) -> Result<T, RepositoryError> {
+    let span = info_span!("repository", op, id = field::Empty);
+    if let Some(id) = id {
+        span.record("id", field::display(id));
+    }
+    let start = Instant::now();
+    let result = call.instrument(span.clone()).await;
+    span.in_scope(|| {
+        debug!(
+            elapsed_us = start.elapsed().as_micros() as u64,
+            ok = result.is_ok(),
+            "finished"
+        )
+    });
+    result
+}
+
+#[async_trait]
+impl<R> Repository for TracingRepository<R>
+where
+    R: Repository + Send + Sync,
+    R::Entity: Identifiable<Id = R::Id>,
+    R::Id: Clone + Into<Uuid>,
+{
+    type Entity = R::Entity;
+    type Id = R::Id;
+
+    async fn create(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        traced("create", Some(entity.id()), self.inner.create(entity)).await
+    }
+
+    async fn create_many(
+        &self,
+        entities: Vec<R::Entity>,
+    ) -> Result<Vec<R::Entity>, RepositoryError> {
+        traced("create_many", None, self.inner.create_many(entities)).await
+    }
+
+    async fn fetch(&self, id: R::Id) -> Result<Option<R::Entity>, RepositoryError> {
+        traced("fetch", Some(id.clone().into()), self.inner.fetch(id)).await
+    }
+
+    async fn fetch_many(&self, ids: &[R::Id]) -> Result<Vec<R::Entity>, RepositoryError> {
+        traced("fetch_many", None, self.inner.fetch_many(ids)).await
+    }
+
+    async fn exists(&self, id: R::Id) -> Result<bool, RepositoryError> {
+        traced("exists", Some(id.clone().into()), self.inner.exists(id)).await
+    }
+
+    async fn update(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        traced("update", Some(entity.id()), self.inner.update(entity)).await
+    }
+
+    async fn delete(&self, id: R::Id) -> Result<(), RepositoryError> {
+        traced("delete", Some(id.clone().into()), self.inner.delete(id)).await
+    }
+
+    async fn list(&self) -> Result<Vec<R::Entity>, RepositoryError> {
+        traced("list", None, self.inner.list()).await
+    }
+
+    async fn list_page(&self, page: Page) -> Result<Vec<R::Entity>, RepositoryError> {
+        traced("list_page", None, self.inner.list_page(page)).await
+    }
+
+    async fn count(&self) -> Result<usize, RepositoryError> {
+        traced("count", None, self.inner.count()).await
+    }
+}
+
+/// A [Repository] decorator whose `delete` only stamps the entity's
+/// [SoftDeletable::deleted_at], so it can be brought back with
+/// [SoftDeleteRepository::restore]. Soft-deleted entities are hidden from
+/// every other read, and are only reachable through
+/// [SoftDeleteRepository::list_deleted].
+pub struct SoftDeleteRepository<R> {
+    inner: R,
+}
+
+impl<R> SoftDeleteRepository<R> {
+    pub fn new(inner: R) -> Self {
+        Self { inner }
+    }
+
+    /// The wrapped repository, which sees soft-deleted entities too.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+}
+
+impl<R> SoftDeleteRepository<R>
+where
+    R: Repository + Send + Sync,
+    R::Entity: SoftDeletable,
+{
+    /// Undo the soft delete of `id` and return the entity (or return
+    /// [RepositoryError::NotFound] if no soft-deleted entity has that id).
+    pub async fn restore(&self, id: R::Id) -> Result<R::Entity, RepositoryError> {
+        let mut entity = self
+            .inner
+            .fetch(id)
+            .await?
+            .filter(SoftDeletable::is_deleted)
+            .ok_or(RepositoryError::NotFound)?;
+        entity.set_deleted_at(None);
+        self.inner.update(entity).await
+    }
+
+    /// Every soft-deleted entity, in the wrapped repository's `list` order.
+    pub async fn list_deleted(&self) -> Result<Vec<R::Entity>, RepositoryError> {
+        let all = self.inner.list().await?;
+        Ok(all.into_iter().filter(SoftDeletable::is_deleted).collect())
+    }
+
+    async fn live(&self) -> Result<Vec<R::Entity>, RepositoryError> {
+        let all = self.inner.list().await?;
+        Ok(all.into_iter().filter(|e| !e.is_deleted()).collect())
+    }
+}
+
+#[async_trait]
+impl<R> Repository for SoftDeleteRepository<R>
+where
+    R: Repository + Send + Sync,
+    R::Entity: SoftDeletable + Identifiable + Clone,
+    R::Id: From<Uuid>,
+{
+    type Entity = R::Entity;
+    type Id = R::Id;
+
+    async fn create(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        self.inner.create(entity).await
+    }
+
+    async fn create_many(
+        &self,
+        entities: Vec<R::Entity>,
+    ) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.create_many(entities).await
+    }
+
+    async fn fetch(&self, id: R::Id) -> Result<Option<R::Entity>, RepositoryError> {
+        let fetched = self.inner.fetch(id).await?;
+        Ok(fetched.filter(|e| !e.is_deleted()))
+    }
+
+    async fn fetch_many(&self, ids: &[R::Id]) -> Result<Vec<R::Entity>, RepositoryError> {
+        let fetched = self.inner.fetch_many(ids).await?;
+        Ok(fetched.into_iter().filter(|e| !e.is_deleted()).collect())
+    }
+
+    /// Like the wrapped `update`, but a soft-deleted entity is
+    /// [RepositoryError::NotFound], as it is to `fetch`, and `entity`'s
+    /// `deleted_at` is ignored: only `delete` and
+    /// [SoftDeleteRepository::restore] change it.
+    async fn update(&self, mut entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        self.fetch(entity.id().into())
+            .await?
+            .ok_or(RepositoryError::NotFound)?;
+        entity.set_deleted_at(None);
+        self.inner.update(entity).await
+    }
+
+    async fn delete(&self, id: R::Id) -> Result<(), RepositoryError> {
+        let mut entity = self.fetch(id).await?.ok_or(RepositoryError::NotFound)?;
+        entity.set_deleted_at(Some(now()));
+        self.inner.update(entity).await.map(drop)
+    }
+
+    async fn list(&self) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.live().await
+    }
+
+    async fn list_page(&self, page: Page) -> Result<Vec<R::Entity>, RepositoryError> {
+        Ok(page.slice(&self.live().await?).to_vec())
+    }
+}
+
+/// A [Repository] decorator that retries `create`, `update` and `fetch` when
+/// the wrapped repository fails with [RepositoryError::Backend], waiting
+/// twice as long before each retry. Every other error, e.g.
+/// [RepositoryError::NotFound] or [RepositoryError::Conflict], is returned
+/// at once, as is the last backend error once the retries run out.
+///
+/// A backend error doesn't mean nothing was written: if the write committed
+/// and only the reply was lost, the retried `create` fails with
+/// [RepositoryError::AlreadyExists], and the retried `update` of a versioned
+/// entity with [RepositoryError::Conflict], although the first attempt
+/// succeeded. Callers that see either after a retry may need to fetch the
+/// entity to find out.
+pub struct RetryingRepository<R> {
+    inner: R,
+    retries: u32,
+    base_delay: Duration,
+}
+
+impl<R> RetryingRepository<R> {
+    /// Retry up to 3 times, waiting 50ms before the first retry.
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            retries: 3,
+            base_delay: Duration::from_millis(50),
+        }
+    }
+
+    /// Retry up to `retries` times after the first attempt.
+    pub fn with_retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Wait `delay` before the first retry, doubling it for each one after.
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// The wrapped repository.
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    async fn retried<T, F, Fut>(&self, mut call: F) -> Result<T, RepositoryError>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, RepositoryError>>,
+    {
+        let mut delay = self.base_delay;
+        let mut retries = 0;
+        loop {
+            match call().await {
+                Err(RepositoryError::Backend(err)) if retries < self.retries => {
+                    debug!(retry = retries + 1, error = %err, "retrying after backend error");
+                    tokio::time::sleep(delay).await;
+                    delay = delay.saturating_mul(2);
+                    retries += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<R> Repository for RetryingRepository<R>
+where
+    R: Repository + Send + Sync,
+    R::Entity: Clone,
+    R::Id: Clone,
+{
+    type Entity = R::Entity;
+    type Id = R::Id;
+
+    async fn create(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        self.retried(|| self.inner.create(entity.clone())).await
+    }
+
+    async fn create_many(
+        &self,
+        entities: Vec<R::Entity>,
+    ) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.create_many(entities).await
+    }
+
+    async fn fetch(&self, id: R::Id) -> Result<Option<R::Entity>, RepositoryError> {
+        self.retried(|| self.inner.fetch(id.clone())).await
+    }
+
+    async fn fetch_many(&self, ids: &[R::Id]) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.fetch_many(ids).await
+    }
+
+    async fn exists(&self, id: R::Id) -> Result<bool, RepositoryError> {
+        self.inner.exists(id).await
+    }
+
+    async fn update(&self, entity: R::Entity) -> Result<R::Entity, RepositoryError> {
+        self.retried(|| self.inner.update(entity.clone())).await
+    }
+
+    async fn delete(&self, id: R::Id) -> Result<(), RepositoryError> {
+        self.inner.delete(id).await
+    }
+
+    async fn list(&self) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.list().await
+    }
+
+    async fn list_page(&self, page: Page) -> Result<Vec<R::Entity>, RepositoryError> {
+        self.inner.list_page(page).await
+    }
+
+    async fn count(&self) -> Result<usize, RepositoryError> {
+        self.inner.count().await
+    }
+}
+
+#[cfg(feature = "sqlite")]
+mod sqlite {
+    use async_trait::async_trait;
+    use serde::{de::DeserializeOwned, Serialize};
+    use sqlx::{sqlite::SqlitePool, Row};
+    use std::{fmt::Display, marker::PhantomData};
+    use uuid::Uuid;
+
+    use super::stamp_created;
+    use crate::{
+        asset::{Asset, AssetId, AssetQueries, Query},
+        common::now,
+        error::RepositoryError,
+        eventstore::{EventEnvelope, EventLog},
+        location::LocationId,
+        Identifiable, Page, Repository,
+    };
+
+    /// An entity stored as one JSON document per row of its own table.
+    pub trait SqliteEntity<Id>: Serialize + DeserializeOwned {
+        /// Name of the entity's table.
+        const TABLE: &'static str;
+        /// The id the entity's row is keyed by.
+        fn row_id(&self) -> Id;
+    }
+
+    impl SqliteEntity<AssetId> for Asset {
+        const TABLE: &'static str = "assets";
+        fn row_id(&self) -> AssetId {
+            self.id.clone()
+        }
+    }
+
+    /// Every query scans every row, since the fields live inside the JSON.
+    #[async_trait]
+    impl AssetQueries for SqliteRepository<Asset, AssetId> {
+        async fn by_location(&self, loc: LocationId) -> Result<Vec<Asset>, RepositoryError> {
+            let all = self.list().await?;
+            Ok(all
+                .into_iter()
+                .filter(|a| a.location_id.as_ref() == Some(&loc))
+                .collect())
+        }
+
+        async fn search(&self, q: &str) -> Result<Vec<Asset>, RepositoryError> {
+            let all = self.list().await?;
+            Ok(all.into_iter().filter(|a| a.matches(q)).collect())
+        }
+
+        async fn find(&self, q: Query) -> Result<Vec<Asset>, RepositoryError> {
+            let all = self.list().await?;
+            Ok(all.into_iter().filter(|a| q.matches(a)).collect())
+        }
+    }
+
+    /// A [Repository] persisting `E` to SQLite in an `(id TEXT PRIMARY KEY,
+    /// data TEXT)` table, with `data` holding the entity as JSON.
+    pub struct SqliteRepository<E, Id> {
+        pool: SqlitePool,
+        _marker: PhantomData<fn() -> (E, Id)>,
+    }
+
+    impl<E: SqliteEntity<Id>, Id> SqliteRepository<E, Id> {
+        /// Open the database at `url` (e.g. `sqlite::memory:`) and create the
+        /// entity's table if it doesn't exist yet.
+        pub async fn connect(url: &str) -> Result<Self, RepositoryError> {
+            let pool = SqlitePool::connect(url).await.map_err(backend)?;
+            let schema = format!(
+                "CREATE TABLE IF NOT EXISTS {} (id TEXT PRIMARY KEY, data TEXT NOT NULL)",
+                E::TABLE
+            );
+            sqlx::query(&schema).execute(&pool).await.map_err(backend)?;
+            Ok(Self {
+                pool,
+                _marker: PhantomData,
+            })
+        }
+    }
+
+    fn backend(err: sqlx::Error) -> RepositoryError {
+        match err.as_database_error() {
+            Some(db) if db.is_unique_violation() => RepositoryError::AlreadyExists,
+            _ => RepositoryError::Backend(err.to_string()),
+        }
+    }
+
+    fn encode<E: Serialize>(entity: &E) -> Result<String, RepositoryError> {
+        serde_json::to_string(entity).map_err(|e| RepositoryError::Serialization(e.to_string()))
+    }
+
+    fn decode<E: DeserializeOwned>(data: &str) -> Result<E, RepositoryError> {
+        serde_json::from_str(data).map_err(|e| RepositoryError::Serialization(e.to_string()))
+    }
+
+    #[async_trait]
+    impl<E, Id> Repository for SqliteRepository<E, Id>
+    where
+        E: SqliteEntity<Id> + Identifiable + Send + Sync,
+        Id: Display + Send + Sync,
+    {
+        type Entity = E;
+        type Id = Id;
+
+        async fn create(&self, mut entity: E) -> Result<E, RepositoryError> {
+            stamp_created(&mut entity);
+            let sql = format!("INSERT INTO {} (id, data) VALUES (?, ?)", E::TABLE);
+            sqlx::query(&sql)
+                .bind(entity.row_id().to_string())
+                .bind(encode(&entity)?)
+                .execute(&self.pool)
+                .await
+                .map_err(backend)?;
+            Ok(entity)
+        }
+
+        async fn create_many(&self, mut entities: Vec<E>) -> Result<Vec<E>, RepositoryError> {
+            let sql = format!("INSERT INTO {} (id, data) VALUES (?, ?)", E::TABLE);
+            let mut tx = self.pool.begin().await.map_err(backend)?;
+            for entity in &mut entities {
+                stamp_created(entity);
+                sqlx::query(&sql)
+                    .bind(entity.row_id().to_string())
+                    .bind(encode(entity)?)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(backend)?;
+            }
+            tx.commit().await.map_err(backend)?;
+            Ok(entities)
+        }
+
+        async fn fetch(&self, id: Id) -> Result<Option<E>, RepositoryError> {
+            let sql = format!("SELECT data FROM {} WHERE id = ?", E::TABLE);
+            let row = sqlx::query(&sql)
+                .bind(id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(backend)?;
+            row.map(|r| decode(r.get("data"))).transpose()
+        }
+
+        async fn fetch_many(&self, ids: &[Id]) -> Result<Vec<E>, RepositoryError> {
+            let sql = format!("SELECT data FROM {} WHERE id = ?", E::TABLE);
+            let mut found = Vec::with_capacity(ids.len());
+            for id in ids {
+                let row = sqlx::query(&sql)
+                    .bind(id.to_string())
+                    .fetch_optional(&self.pool)
+                    .await
+                    .map_err(backend)?;
+                if let Some(row) = row {
+                    found.push(decode(row.get("data"))?);
+                }
+            }
+            Ok(found)
+        }
+
+        async fn update(&self, mut entity: E) -> Result<E, RepositoryError> {
+            // The creation time isn't the caller's to change, so it's carried
+            // over from the stored row.
+            if entity.created_at().is_some() {
+                let stored = self.fetch(entity.row_id()).await?;
+                let stored = stored.ok_or(RepositoryError::NotFound)?;
+                let now = now();
+                entity.set_timestamps(stored.created_at().unwrap_or(now), now);
+            }
+            // A versioned entity only replaces the row it was read from; the
+            // check is part of the statement so two racing updates can't both
+            // succeed.
+            let expected = entity.version();
+            let mut sql = format!("UPDATE {} SET data = ? WHERE id = ?", E::TABLE);
+            if let Some(expected) = expected {
+                entity.set_version(expected + 1);
+                sql.push_str(" AND COALESCE(json_extract(data, '$.version'), 0) = ?");
+            }
+            let mut query = sqlx::query(&sql)
+                .bind(encode(&entity)?)
+                .bind(entity.row_id().to_string());
+            if let Some(expected) = expected {
+                query = query.bind(expected as i64);
+            }
+            let done = query.execute(&self.pool).await.map_err(backend)?;
+            if done.rows_affected() == 0 {
+                return match (expected, self.fetch(entity.row_id()).await?) {
+                    (Some(expected), Some(stored)) => Err(RepositoryError::Conflict {
+                        expected,
+                        actual: stored.version().unwrap_or_default(),
+                    }),
+                    _ => Err(RepositoryError::NotFound),
+                };
+            }
+            Ok(entity)
+        }
+
+        async fn delete(&self, id: Id) -> Result<(), RepositoryError> {
+            let sql = format!("DELETE FROM {} WHERE id = ?", E::TABLE);
+            let done = sqlx::query(&sql)
+                .bind(id.to_string())
+                .execute(&self.pool)
+                .await
+                .map_err(backend)?;
+            if done.rows_affected() == 0 {
+                return Err(RepositoryError::NotFound);
+            }
+            Ok(())
+        }
+
+        async fn list(&self) -> Result<Vec<E>, RepositoryError> {
+            let sql = format!("SELECT data FROM {} ORDER BY rowid", E::TABLE);
+            let rows = sqlx::query(&sql)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(backend)?;
+            rows.iter().map(|r| decode(r.get("data"))).collect()
+        }
+
+        async fn list_page(&self, page: Page) -> Result<Vec<E>, RepositoryError> {
+            let sql = format!(
+                "SELECT data FROM {} ORDER BY rowid LIMIT ? OFFSET ?",
+                E::TABLE
+            );
+            let rows = sqlx::query(&sql)
+                .bind(page.limit as i64)
+                .bind(page.offset as i64)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(backend)?;
+            rows.iter().map(|r| decode(r.get("data"))).collect()
+        }
+
+        async fn count(&self) -> Result<usize, RepositoryError> {
+            let sql = format!("SELECT COUNT(*) FROM {}", E::TABLE);
+            let n: i64 = sqlx::query_scalar(&sql)
+                .fetch_one(&self.pool)
+                .await
+                .map_err(backend)?;
+            Ok(n as usize)
+        }
+    }
+
+    /// An append-only log of `E` events persisted to SQLite, for histories
+    /// that outlive the process, unlike an [EventStore](crate::eventstore::EventStore)'s.
+    /// Each row of the `events` table holds one [EventEnvelope] as JSON,
+    /// keyed by its aggregate and sequence.
+    pub struct SqliteEventLog<E> {
+        pool: SqlitePool,
+        _marker: PhantomData<fn() -> E>,
+    }
+
+    impl<E: Serialize + DeserializeOwned> SqliteEventLog<E> {
+        /// Open the database at `url` and create the `events` table if it
+        /// doesn't exist yet.
+        pub async fn connect(url: &str) -> Result<Self, RepositoryError> {
+            let pool = SqlitePool::connect(url).await.map_err(backend)?;
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS events (aggregate_id TEXT NOT NULL, \
+                 sequence INTEGER NOT NULL, data TEXT NOT NULL, \
+                 PRIMARY KEY (aggregate_id, sequence))",
+            )
+            .execute(&pool)
+            .await
+            .map_err(backend)?;
+            Ok(Self {
+                pool,
+                _marker: PhantomData,
+            })
+        }
+
+        /// Append `events` to the aggregate's log and return its new version,
+        /// as [EventStore::append](crate::eventstore::EventStore::append)
+        /// does: if the log is no longer at `expected_version`, nothing is
+        /// written and [RepositoryError::Conflict] is returned.
+        pub async fn append(
+            &self,
+            aggregate_id: Uuid,
+            expected_version: u64,
+            events: Vec<E>,
+        ) -> Result<u64, RepositoryError> {
+            let mut tx = self.pool.begin().await.map_err(backend)?;
+            let actual = version(&mut tx, aggregate_id).await?;
+            if actual != expected_version {
+                return Err(RepositoryError::Conflict {
+                    expected: expected_version,
+                    actual,
+                });
+            }
+            let occurred_at = now();
+            let mut sequence = actual;
+            for event in events {
+                sequence += 1;
+                let envelope = EventEnvelope {
+                    event_id: Uuid::new_v4(),
+                    aggregate_id,
+                    sequence,
+                    occurred_at,
+                    event,
+                };
+                let inserted = sqlx::query(
+                    "INSERT INTO events (aggregate_id, sequence, data) VALUES (?, ?, ?)",
+                )
+                .bind(aggregate_id.to_string())
+                .bind(sequence as i64)
+                .bind(encode(&envelope)?)
+                .execute(&mut *tx)
+                .await
+                .map_err(backend);
+                // Another writer appended at the same sequence first.
+                if let Err(RepositoryError::AlreadyExists) = inserted {
+                    drop(tx);
+                    let mut conn = self.pool.acquire().await.map_err(backend)?;
+                    return Err(RepositoryError::Conflict {
+                        expected: expected_version,
+                        actual: version(&mut conn, aggregate_id).await?,
+                    });
+                }
+                inserted?;
+            }
+            tx.commit().await.map_err(backend)?;
+            Ok(sequence)
+        }
+
+        /// Every event recorded for the aggregate, oldest first.
+        pub async fn load(
+            &self,
+            aggregate_id: Uuid,
+        ) -> Result<Vec<EventEnvelope<E>>, RepositoryError> {
+            let rows =
+                sqlx::query("SELECT data FROM events WHERE aggregate_id = ? ORDER BY sequence")
+                    .bind(aggregate_id.to_string())
+                    .fetch_all(&self.pool)
+                    .await
+                    .map_err(backend)?;
+            rows.iter().map(|r| decode(r.get("data"))).collect()
+        }
+    }
+
+    /// The number of events recorded for the aggregate.
+    async fn version(
+        conn: &mut sqlx::SqliteConnection,
+        aggregate_id: Uuid,
+    ) -> Result<u64, RepositoryError> {
+        let n: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM events WHERE aggregate_id = ?")
+            .bind(aggregate_id.to_string())
+            .fetch_one(conn)
+            .await
+            .map_err(backend)?;
+        Ok(n as u64)
+    }
+
+    #[async_trait]
+    impl<E> EventLog<E> for SqliteEventLog<E>
+    where
+        E: Serialize + DeserializeOwned + Send + Sync,
+    {
+        async fn history(
+            &self,
+            aggregate_id: Uuid,
+        ) -> Result<Vec<EventEnvelope<E>>, RepositoryError> {
+            self.load(aggregate_id).await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::{Duration, UNIX_EPOCH},
+    };
+    use tokio::sync::Notify;
+
+    use crate::{
+        asset::{Asset, AssetId, Quantity},
+        location::would_cycle,
+    };
+
+    fn asset(name: &str) -> Asset {
+        Asset::new(
+            AssetId(Uuid::now_v7()),
+            name.parse().unwrap(),
+            None::<String>,
+            1u32,
+        )
+    }
+
+    fn names<E: Named>(entities: &[E]) -> Vec<&str> {
+        entities.iter().map(|e| e.name()).collect()
+    }
+
+    #[tokio::test]
+    async fn assets_round_trip() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+
+        let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        assert_eq!(fetched.id, drill.id);
+        assert!(matches!(
+            repo.create(drill.clone()).await,
+            Err(RepositoryError::AlreadyExists)
+        ));
+
+        repo.delete(drill.id.clone()).await.unwrap();
+        assert!(repo.fetch(drill.id.clone()).await.unwrap().is_none());
+        assert!(matches!(
+            repo.delete(drill.id).await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn updates_bump_the_version() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+
+        let updated = repo.update(drill.clone()).await.unwrap();
+        assert_eq!(updated.version, 1);
+        assert_eq!(
+            repo.fetch(drill.id.clone()).await.unwrap().unwrap().version,
+            1
+        );
+        assert!(matches!(
+            repo.update(drill).await,
+            Err(RepositoryError::Conflict {
+                expected: 0,
+                actual: 1
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn clean_merging_updates_store_the_entity_as_is() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let created = repo.create(asset("drill")).await.unwrap();
+        let mut drill = created.clone();
+        drill.quantity = Quantity::new(4);
+
+        let updated = repo.update_merging(&created, drill).await.unwrap();
+        assert_eq!(updated.quantity, Quantity::new(4));
+        assert_eq!(updated.version, 1);
+    }
+
+    #[tokio::test]
+    async fn conflicting_merging_updates_add_their_quantity_changes() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let created = repo.create(asset("drill")).await.unwrap();
+        let (mut first, mut second) = (created.clone(), created.clone());
+        first.quantity = Quantity::new(3);
+        repo.update(first).await.unwrap();
+
+        second.name = "Cordless drill".parse().unwrap();
+        second.quantity = Quantity::new(2);
+        let merged = repo.update_merging(&created, second.clone()).await.unwrap();
+        assert_eq!(merged.quantity, Quantity::new(4));
+        assert_eq!(merged.name, "Cordless drill");
+        assert_eq!(merged.version, 2);
+        assert_eq!(repo.fetch(second.id).await.unwrap().unwrap(), merged);
+    }
+
+    #[tokio::test]
+    async fn updates_advance_only_updated_at() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let created = repo.create(asset("drill")).await.unwrap();
+        assert_eq!(created.created_at, created.updated_at);
+        assert!(created.created_at > UNIX_EPOCH);
+
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        let mut stale = created.clone();
+        stale.created_at = UNIX_EPOCH;
+        let updated = repo.update(stale).await.unwrap();
+        assert_eq!(updated.created_at, created.created_at);
+        assert!(updated.updated_at > created.updated_at);
+        let fetched = repo.fetch(created.id).await.unwrap().unwrap();
+        assert_eq!(fetched.created_at, created.created_at);
+        assert_eq!(fetched.updated_at, updated.updated_at);
+    }
+
+    #[tokio::test]
+    async fn random_ids_still_list_in_insertion_order() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let labels: Vec<String> = (0..16).map(|n| format!("asset {n}")).collect();
+        for label in &labels {
+            let mut asset = asset(label);
+            asset.id = AssetId(Uuid::new_v4());
+            repo.create(asset).await.unwrap();
+        }
+        let mut moved = repo.list().await.unwrap().remove(3);
+        moved.location_id = Some(LocationId::new());
+        repo.update(moved).await.unwrap();
+
+        let listed: Vec<String> = repo
+            .list()
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|a| a.name.to_string())
+            .collect();
+        assert_eq!(listed, labels);
+    }
+
+    #[tokio::test]
+    async fn assets_list_in_insertion_order() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let assets: Vec<Asset> = ["a", "b", "c"].into_iter().map(asset).collect();
+        repo.create_many(assets).await.unwrap();
+
+        assert_eq!(names(&repo.list().await.unwrap()), vec!["a", "b", "c"]);
+        assert_eq!(
+            names(&repo.list_page(Page::new(1, 1)).await.unwrap()),
+            vec!["b"]
+        );
+        assert_eq!(repo.count().await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn by_location_returns_only_that_locations_assets() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let (shed, garage) = (LocationId::new(), LocationId::new());
+        let placed = |name, loc: &LocationId| {
+            let mut a = asset(name);
+            a.location_id = Some(loc.clone());
+            a
+        };
+        repo.create_many(vec![
+            placed("rake", &shed),
+            placed("car", &garage),
+            placed("shovel", &shed),
+            asset("unplaced"),
+        ])
+        .await
+        .unwrap();
+
+        let in_shed = repo.by_location(shed).await.unwrap();
+        assert_eq!(names(&in_shed), vec!["rake", "shovel"]);
+        let in_garage = repo.by_location(garage).await.unwrap();
+        assert_eq!(names(&in_garage), vec!["car"]);
+        assert!(repo
+            .by_location(LocationId::new())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn bulk_move_reports_each_id_without_stopping() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let (rake, shovel) = (asset("rake"), asset("shovel"));
+        repo.create_many(vec![rake.clone(), shovel.clone()])
+            .await
+            .unwrap();
+        let missing = AssetId(Uuid::now_v7());
+        let shed = LocationId::new();
+
+        let report = repo
+            .bulk_move(
+                &[rake.id.clone(), missing.clone(), shovel.id.clone()],
+                shed.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(report.succeeded, vec![rake.id, shovel.id]);
+        assert!(matches!(
+            &report.failed[..],
+            [(id, RepositoryError::NotFound)] if *id == missing
+        ));
+        assert_eq!(
+            names(&repo.by_location(shed).await.unwrap()),
+            vec!["rake", "shovel"]
+        );
+    }
+
+    #[tokio::test]
+    async fn search_matches_substrings_ignoring_case() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let mut saw = asset("Table saw");
+        saw.description = Some("Dewalt, needs a new blade".into());
+        repo.create_many(vec![asset("Cordless drill"), saw, asset("Drill bits")])
+            .await
+            .unwrap();
+
+        let found = repo.search("DRILL").await.unwrap();
+        assert_eq!(names(&found), vec!["Cordless drill", "Drill bits"]);
+        assert_eq!(
+            names(&repo.search("blade").await.unwrap()),
+            vec!["Table saw"]
+        );
+        assert!(repo.search("hammer").await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn find_returns_assets_passing_every_filter() {
+        let repo = InMemoryRepository::<Asset>::new();
+        let shed = LocationId::new();
+        let stock = |name, quantity, loc: Option<&LocationId>| {
+            let mut a = asset(name);
+            a.quantity = Quantity::new(quantity);
+            a.location_id = loc.cloned();
+            a
+        };
+        repo.create_many(vec![
+            stock("Cordless drill", 1, Some(&shed)),
+            stock("Drill bits", 12, Some(&shed)),
+            stock("Hammer drill", 3, None),
+            stock("Saw", 5, Some(&shed)),
+        ])
+        .await
+        .unwrap();
+
+        let found = repo
+            .find(
+                Query::new()
+                    .name_contains("DRILL")
+                    .in_location(shed.clone()),
+            )
+            .await
+            .unwrap();
+        assert_eq!(names(&found), vec!["Cordless drill", "Drill bits"]);
+        let found = repo
+            .find(Query::new().name_contains("drill").quantity_gte(3))
+            .await
+            .unwrap();
+        assert_eq!(names(&found), vec!["Drill bits", "Hammer drill"]);
+        let found = repo
+            .find(Query::new().quantity_gte(2).quantity_lte(5))
+            .await
+            .unwrap();
+        assert_eq!(names(&found), vec!["Hammer drill", "Saw"]);
+        assert_eq!(repo.find(Query::new()).await.unwrap().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn locations_round_trip() {
+        let repo = InMemoryRepository::<Location>::new();
+        let shed = Location::new(
+            LocationId::new_v7(),
+            "Shed".parse().unwrap(),
+            None::<String>,
+        );
+        let garage = Location::new(
+            LocationId::new_v7(),
+            "Garage".parse().unwrap(),
+            None::<String>,
+        );
+        repo.create(shed.clone()).await.unwrap();
+        repo.create(garage.clone()).await.unwrap();
+
+        let fetched = repo.fetch(shed.id.clone()).await.unwrap().unwrap();
+        assert_eq!(fetched.name, "Shed");
+        assert_eq!(names(&repo.list().await.unwrap()), vec!["Shed", "Garage"]);
+        let found = repo.find_by_name("garage").await.unwrap().unwrap();
+        assert_eq!(found.id, garage.id);
+
+        repo.delete(shed.id.clone()).await.unwrap();
+        assert_eq!(names(&repo.list().await.unwrap()), vec!["Garage"]);
+        assert!(matches!(
+            repo.update(shed).await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    /// `warehouse ▸ aisle ▸ shelf`, plus a separate `yard`.
+    async fn nested() -> (InMemoryRepository<Location>, [LocationId; 4]) {
+        let repo = InMemoryRepository::<Location>::new();
+        let ids = [(); 4].map(|_| LocationId::new_v7());
+        let [warehouse, aisle, shelf, yard] = ids.clone();
+        let place = |id: &LocationId, name: &str, parent: Option<&LocationId>| {
+            let mut loc = Location::new(id.clone(), name.parse().unwrap(), None::<String>);
+            loc.parent_id = parent.cloned();
+            loc
+        };
+        repo.create_many(vec![
+            place(&warehouse, "Warehouse", None),
+            place(&aisle, "Aisle 3", Some(&warehouse)),
+            place(&shelf, "Shelf B", Some(&aisle)),
+            place(&yard, "Yard", None),
+        ])
+        .await
+        .unwrap();
+        (repo, ids)
+    }
+
+    #[tokio::test]
+    async fn children_lists_direct_descendants_only() {
+        let (repo, [warehouse, aisle, shelf, _]) = nested().await;
+        assert_eq!(
+            names(&repo.children(warehouse).await.unwrap()),
+            vec!["Aisle 3"]
+        );
+        assert_eq!(names(&repo.children(aisle).await.unwrap()), vec!["Shelf B"]);
+        assert!(repo.children(shelf).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn would_cycle_rejects_reparenting_under_a_descendant() {
+        let (repo, [warehouse, aisle, shelf, yard]) = nested().await;
+        assert!(would_cycle(&repo, &warehouse, &shelf).await.unwrap());
+        assert!(would_cycle(&repo, &aisle, &aisle).await.unwrap());
+        assert!(!would_cycle(&repo, &shelf, &warehouse).await.unwrap());
+        assert!(!would_cycle(&repo, &warehouse, &yard).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn ancestry_runs_from_root_to_location() {
+        let (repo, [warehouse, _, shelf, yard]) = nested().await;
+        let chain = repo.ancestry(shelf).await.unwrap();
+        assert_eq!(names(&chain), vec!["Warehouse", "Aisle 3", "Shelf B"]);
+        assert_eq!(
+            names(&repo.ancestry(warehouse).await.unwrap()),
+            vec!["Warehouse"]
+        );
+        assert_eq!(names(&repo.ancestry(yard).await.unwrap()), vec!["Yard"]);
+        assert!(matches!(
+            repo.ancestry(LocationId::new()).await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    async fn ancestry_errors_on_a_self_parent() {
+        let repo = InMemoryRepository::<Location>::new();
+        let id = LocationId::new();
+        let mut corrupt = Location::new(id.clone(), "Loop".parse().unwrap(), None::<String>);
+        corrupt.parent_id = Some(id.clone());
+        repo.create(corrupt).await.unwrap();
+
+        assert!(matches!(
+            repo.ancestry(id).await,
+            Err(RepositoryError::Corrupt(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn tagging_links_assets_and_tags() {
+        let tagging = InMemoryTagging::new();
+        let (drill, saw, rake) = (asset("drill"), asset("saw"), asset("rake"));
+        let (power, garden) = (TagId::new(), TagId::new());
+
+        tagging.tag(drill.id.clone(), power.clone()).await.unwrap();
+        tagging.tag(saw.id.clone(), power.clone()).await.unwrap();
+        tagging.tag(saw.id.clone(), power.clone()).await.unwrap();
+        tagging.tag(rake.id.clone(), garden.clone()).await.unwrap();
+
+        let mut powered = tagging.assets_with_tag(power.clone()).await.unwrap();
+        powered.sort_by_key(|a| a.0);
+        let mut expected = vec![drill.id.clone(), saw.id.clone()];
+        expected.sort_by_key(|a| a.0);
+        assert_eq!(powered, expected);
+        assert_eq!(
+            tagging.tags_of(rake.id.clone()).await.unwrap(),
+            vec![garden.clone()]
+        );
+
+        tagging.untag(saw.id.clone(), power.clone()).await.unwrap();
+        assert_eq!(
+            tagging.assets_with_tag(power.clone()).await.unwrap(),
+            vec![drill.id]
+        );
+        assert!(matches!(
+            tagging.untag(saw.id, power).await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+
+    #[tokio::test]
+    #[tracing_test::traced_test]
+    async fn tracing_repository_spans_create_and_fetch() {
+        let repo = TracingRepository::new(InMemoryRepository::<Asset>::new());
+        let drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+        let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        assert_eq!(fetched.name, "drill");
+
+        let id = drill.id.to_string();
+        assert!(logs_contain(&format!(
+            "repository{{op=\"create\" id={id}}}"
+        )));
+        assert!(logs_contain(&format!("repository{{op=\"fetch\" id={id}}}")));
+        assert!(logs_contain("elapsed_us="));
+    }
+
+    /// Counts `fetch` calls reaching the backing store, and can hold one call
+    /// part-way through so a test can run another in the middle of it.
+    #[derive(Default)]
+    struct CountingRepo {
+        inner: InMemoryRepository<Asset>,
+        fetches: AtomicUsize,
+        hold: Mutex<Option<&'static str>>,
+        reached: Notify,
+        release: Notify,
+    }
+
+    impl CountingRepo {
+        /// Hold the next `op`: a fetch once it has read, an update or delete
+        /// before it writes. `reached` fires when it's held, and `release`
+        /// lets it go on.
+        fn hold(&self, op: &'static str) {
+            *self.hold.lock().unwrap() = Some(op);
+        }
+
+        async fn held(&self, op: &'static str) {
+            let held = self.hold.lock().unwrap().take_if(|held| *held == op);
+            if held.is_some() {
+                self.reached.notify_one();
+                self.release.notified().await;
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Repository for CountingRepo {
+        type Entity = Asset;
+        type Id = AssetId;
+
+        async fn create(&self, entity: Asset) -> Result<Asset, RepositoryError> {
+            self.inner.create(entity).await
+        }
+
+        async fn create_many(&self, entities: Vec<Asset>) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.create_many(entities).await
+        }
+
+        async fn fetch(&self, id: AssetId) -> Result<Option<Asset>, RepositoryError> {
+            self.fetches.fetch_add(1, Ordering::SeqCst);
+            let fetched = self.inner.fetch(id).await;
+            self.held("fetch").await;
+            fetched
+        }
+
+        async fn fetch_many(&self, ids: &[AssetId]) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.fetch_many(ids).await
+        }
+
+        async fn update(&self, entity: Asset) -> Result<Asset, RepositoryError> {
+            self.held("update").await;
+            self.inner.update(entity).await
+        }
+
+        async fn delete(&self, id: AssetId) -> Result<(), RepositoryError> {
+            self.held("delete").await;
+            self.inner.delete(id).await
+        }
+
+        async fn list(&self) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.list().await
+        }
+
+        async fn list_page(&self, page: Page) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.list_page(page).await
+        }
+    }
+
+    fn fetches(repo: &CachingRepository<CountingRepo>) -> usize {
+        repo.inner().fetches.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test]
+    async fn repeated_fetch_hits_the_cache() {
+        let repo = CachingRepository::new(CountingRepo::default());
+        let drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+
+        repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        let again = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        assert_eq!(again.name, "drill");
+        assert_eq!(fetches(&repo), 1);
+    }
+
+    #[tokio::test]
+    async fn update_evicts_the_cached_entity() {
+        let repo = CachingRepository::new(CountingRepo::default());
+        let mut drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+        repo.fetch(drill.id.clone()).await.unwrap();
+
+        drill.name = "hammer drill".parse().unwrap();
+        repo.update(drill.clone()).await.unwrap();
+        let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        assert_eq!(fetched.name, "hammer drill");
+        assert_eq!(fetches(&repo), 2);
+    }
+
+    #[tokio::test]
+    async fn a_fetch_during_an_update_does_not_cache_the_old_entity() {
+        let repo = CachingRepository::new(CountingRepo::default());
+        let drill = repo.create(asset("drill")).await.unwrap();
+        let mut renamed = drill.clone();
+        renamed.name = "hammer drill".parse().unwrap();
+
+        repo.inner().hold("update");
+        let (updated, during) = tokio::join!(repo.update(renamed), async {
+            repo.inner().reached.notified().await;
+            let during = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+            repo.inner().release.notify_one();
+            during
+        });
+        updated.unwrap();
+        assert_eq!(during.name, "drill");
+        let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        assert_eq!(fetched.name, "hammer drill");
+    }
+
+    #[tokio::test]
+    async fn a_fetch_that_read_before_an_update_does_not_cache_it() {
+        let repo = CachingRepository::new(CountingRepo::default());
+        let drill = repo.create(asset("drill")).await.unwrap();
+        let mut renamed = drill.clone();
+        renamed.name = "hammer drill".parse().unwrap();
+
+        repo.inner().hold("fetch");
+        let (raced, ()) = tokio::join!(repo.fetch(drill.id.clone()), async {
+            repo.inner().reached.notified().await;
+            repo.update(renamed).await.unwrap();
+            repo.inner().release.notify_one();
+        });
+        assert_eq!(raced.unwrap().unwrap().name, "drill");
+        let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+        assert_eq!(fetched.name, "hammer drill");
+    }
+
+    #[tokio::test]
+    async fn delete_evicts_the_cached_entity() {
+        let repo = CachingRepository::new(CountingRepo::default());
+        let drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+        repo.fetch(drill.id.clone()).await.unwrap();
+
+        repo.delete(drill.id.clone()).await.unwrap();
+        assert!(repo.fetch(drill.id).await.unwrap().is_none());
+    }
+
+    /// Fails the first `failures` calls to `create`, `update` and `fetch`
+    /// with a backend error, counting every call.
+    #[derive(Default)]
+    struct FlakyRepo {
+        inner: InMemoryRepository<Asset>,
+        failures: AtomicUsize,
+        calls: AtomicUsize,
+    }
+
+    impl FlakyRepo {
+        fn failing(failures: usize) -> Self {
+            Self {
+                failures: AtomicUsize::new(failures),
+                ..Self::default()
+            }
+        }
+
+        fn call(&self) -> Result<(), RepositoryError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let failing = self
+                .failures
+                .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| n.checked_sub(1))
+                .is_ok();
+            match failing {
+                true => Err(RepositoryError::Backend("database is locked".into())),
+                false => Ok(()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Repository for FlakyRepo {
+        type Entity = Asset;
+        type Id = AssetId;
+
+        async fn create(&self, entity: Asset) -> Result<Asset, RepositoryError> {
+            self.call()?;
+            self.inner.create(entity).await
+        }
+
+        async fn create_many(&self, entities: Vec<Asset>) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.create_many(entities).await
+        }
+
+        async fn fetch(&self, id: AssetId) -> Result<Option<Asset>, RepositoryError> {
+            self.call()?;
+            self.inner.fetch(id).await
+        }
+
+        async fn fetch_many(&self, ids: &[AssetId]) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.fetch_many(ids).await
+        }
+
+        async fn update(&self, entity: Asset) -> Result<Asset, RepositoryError> {
+            self.call()?;
+            self.inner.update(entity).await
+        }
+
+        async fn delete(&self, id: AssetId) -> Result<(), RepositoryError> {
+            self.inner.delete(id).await
+        }
+
+        async fn list(&self) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.list().await
+        }
+
+        async fn list_page(&self, page: Page) -> Result<Vec<Asset>, RepositoryError> {
+            self.inner.list_page(page).await
+        }
+    }
+
+    fn calls(repo: &RetryingRepository<FlakyRepo>) -> usize {
+        repo.inner().calls.load(Ordering::SeqCst)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn backend_errors_are_retried_with_backoff() {
+        let repo = RetryingRepository::new(FlakyRepo::failing(2))
+            .with_base_delay(Duration::from_millis(100));
+        let start = tokio::time::Instant::now();
+
+        let drill = repo.create(asset("drill")).await.unwrap();
+        assert_eq!(calls(&repo), 3);
+        assert_eq!(start.elapsed(), Duration::from_millis(100 + 200));
+        assert_eq!(repo.fetch(drill.id).await.unwrap().unwrap().name, "drill");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_run_out_with_the_last_backend_error() {
+        let repo = RetryingRepository::new(FlakyRepo::failing(5)).with_retries(2);
+        assert!(matches!(
+            repo.fetch(AssetId(Uuid::now_v7())).await,
+            Err(RepositoryError::Backend(_))
+        ));
+        assert_eq!(calls(&repo), 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn not_found_and_conflicts_are_not_retried() {
+        let repo = RetryingRepository::new(FlakyRepo::default());
+        let start = tokio::time::Instant::now();
+        assert!(matches!(
+            repo.update(asset("drill")).await,
+            Err(RepositoryError::NotFound)
+        ));
+        assert_eq!(calls(&repo), 1);
+
+        let drill = repo.create(asset("drill")).await.unwrap();
+        repo.update(drill.clone()).await.unwrap();
+        assert!(matches!(
+            repo.update(drill).await,
+            Err(RepositoryError::Conflict { .. })
+        ));
+        assert_eq!(calls(&repo), 4);
+        assert_eq!(start.elapsed(), Duration::ZERO);
+    }
+
+    #[tokio::test]
+    async fn soft_deleted_assets_are_hidden_until_restored() {
+        let repo = SoftDeleteRepository::new(InMemoryRepository::<Asset>::new());
+        let (drill, saw) = (asset("drill"), asset("saw"));
+        repo.create_many(vec![drill.clone(), saw.clone()])
+            .await
+            .unwrap();
+
+        repo.delete(drill.id.clone()).await.unwrap();
+        assert_eq!(names(&repo.list().await.unwrap()), vec!["saw"]);
+        assert_eq!(repo.count().await.unwrap(), 1);
+        assert!(repo.fetch(drill.id.clone()).await.unwrap().is_none());
+        let deleted = repo.list_deleted().await.unwrap();
+        assert_eq!(names(&deleted), vec!["drill"]);
+        assert!(deleted[0].deleted_at.is_some());
+        assert!(repo
+            .inner()
+            .fetch(drill.id.clone())
+            .await
+            .unwrap()
+            .is_some());
+
+        let restored = repo.restore(drill.id.clone()).await.unwrap();
+        assert!(restored.deleted_at.is_none());
+        assert_eq!(names(&repo.list().await.unwrap()), vec!["drill", "saw"]);
+        assert!(repo.list_deleted().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn soft_deleted_assets_cannot_be_updated() {
+        let repo = SoftDeleteRepository::new(InMemoryRepository::<Asset>::new());
+        let drill = repo.create(asset("drill")).await.unwrap();
+        repo.delete(drill.id.clone()).await.unwrap();
+
+        let mut renamed = drill.clone();
+        renamed.name = "hammer".parse().unwrap();
+        assert!(matches!(
+            repo.update(renamed).await,
+            Err(RepositoryError::NotFound)
+        ));
+        let deleted = repo.list_deleted().await.unwrap();
+        assert_eq!(names(&deleted), vec!["drill"]);
+        assert!(repo.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn updates_leave_deleted_at_alone() {
+        let repo = SoftDeleteRepository::new(InMemoryRepository::<Asset>::new());
+        let mut drill = repo.create(asset("drill")).await.unwrap();
+
+        drill.deleted_at = Some(UNIX_EPOCH);
+        let updated = repo.update(drill).await.unwrap();
+        assert!(updated.deleted_at.is_none());
+        assert_eq!(names(&repo.list().await.unwrap()), vec!["drill"]);
+    }
+
+    #[tokio::test]
+    async fn restoring_a_live_asset_is_not_found() {
+        let repo = SoftDeleteRepository::new(InMemoryRepository::<Asset>::new());
+        let drill = asset("drill");
+        repo.create(drill.clone()).await.unwrap();
+
+        assert!(matches!(
+            repo.restore(drill.id.clone()).await,
+            Err(RepositoryError::NotFound)
+        ));
+        repo.delete(drill.id.clone()).await.unwrap();
+        assert!(matches!(
+            repo.delete(drill.id).await,
+            Err(RepositoryError::NotFound)
+        ));
+    }
+}
@@ -1,4 +1,101 @@
+use std::fmt;
+
+use crate::auth::Action;
+use crate::user::Role;
+
 #[derive(Debug)]
 pub enum CoreError {
     Unknown,
 }
+
+/// Why a [Repository](crate::common::Repository) operation failed.
+#[derive(Debug, thiserror::Error)]
+pub enum RepositoryError {
+    /// No entity with the requested id is stored.
+    #[error("entity not found")]
+    NotFound,
+    /// An entity with the same id is already stored.
+    #[error("entity already exists")]
+    AlreadyExists,
+    /// The update was made to version `expected` of the entity, but the
+    /// stored one has since moved on to `actual`.
+    #[error("version conflict: expected {expected}, found {actual}")]
+    Conflict { expected: u64, actual: u64 },
+    /// The storage backend failed or is unreachable.
+    #[error("backend error: {0}")]
+    Backend(String),
+    /// An entity could not be encoded or decoded.
+    #[error("serialization error: {0}")]
+    Serialization(String),
+    /// Stored entities are inconsistent with each other (e.g. a loop of
+    /// parent references).
+    #[error("corrupt data: {0}")]
+    Corrupt(String),
+}
+
+impl From<anyhow::Error> for RepositoryError {
+    fn from(err: anyhow::Error) -> Self {
+        RepositoryError::Backend(err.to_string())
+    }
+}
+
+/// A [Quantity](crate::asset::Quantity) would leave the range `0..=u32::MAX`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+pub enum QuantityError {
+    #[error("quantity cannot be negative")]
+    Negative,
+    #[error("quantity is too large")]
+    Overflow,
+}
+
+/// A [Tag](crate::tag::Tag) failed validation.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TagError {
+    #[error("invalid tag name: {0}")]
+    Name(#[from] ValidationError),
+    #[error("tag color {0:?} is not a #rrggbb hex color")]
+    InvalidColor(String),
+}
+
+/// A [Name](crate::validation::Name) was rejected.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ValidationError {
+    #[error("name must not be empty")]
+    EmptyName,
+    /// The name is this many characters long.
+    #[error("name is {0} characters long; the limit is {max}", max = crate::validation::Name::MAX_LEN)]
+    NameTooLong(usize),
+}
+
+/// Signing in or checking credentials failed.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuthError {
+    /// A password could not be hashed, or a stored hash is malformed.
+    #[error("password hashing failed: {0}")]
+    Hash(String),
+    /// A session token was valid once but its expiry has passed.
+    #[error("session token has expired")]
+    TokenExpired,
+    /// A session token is malformed or was not signed with our secret.
+    #[error("invalid session token: {0}")]
+    InvalidToken(String),
+    /// The user's role doesn't permit the action.
+    #[error("{role:?} users may not {action:?}")]
+    Forbidden { role: Role, action: Action },
+}
+
+/// A string could not be parsed as a `RepositoryId`.
+#[derive(Debug)]
+pub struct IdParseError(pub(crate) uuid::Error);
+
+impl fmt::Display for IdParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid id: {}", self.0)
+    }
+}
+
+impl std::error::Error for IdParseError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
@@ -0,0 +1,384 @@
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+    time::SystemTime,
+};
+use uuid::Uuid;
+
+use crate::common::{now, Aggregate};
+use crate::error::RepositoryError;
+
+/// Aggregate state captured after `version` events, so loading doesn't have
+/// to replay the whole history.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot<A> {
+    /// Number of events folded into `state`.
+    pub version: u64,
+    /// The aggregate as it was after applying `version` events.
+    pub state: A,
+}
+
+impl<A> Snapshot<A> {
+    pub fn new(version: u64, state: A) -> Self {
+        Self { version, state }
+    }
+}
+
+/// A recorded event plus the metadata needed to order and audit it.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct EventEnvelope<E> {
+    pub event_id: Uuid,
+    pub aggregate_id: Uuid,
+    /// Position in the aggregate's log, starting at 1 with no gaps.
+    pub sequence: u64,
+    /// Serialized as an RFC 3339 string, like entity timestamps.
+    #[serde(with = "crate::common::rfc3339")]
+    pub occurred_at: SystemTime,
+    pub event: E,
+}
+
+/// One aggregate's events, each with its store-wide append number.
+type Log<E> = Vec<(u64, EventEnvelope<E>)>;
+
+/// In-memory, append-only log of events per aggregate id.
+pub struct EventStore<A: Aggregate> {
+    /// Each aggregate's log; [EventStore::load_all] orders by the append
+    /// numbers.
+    events: Mutex<HashMap<Uuid, Log<A::Event>>>,
+    appended: AtomicU64,
+    snapshots: Mutex<HashMap<Uuid, Snapshot<A>>>,
+}
+
+impl<A: Aggregate> Default for EventStore<A> {
+    fn default() -> Self {
+        Self {
+            events: Default::default(),
+            appended: AtomicU64::new(0),
+            snapshots: Default::default(),
+        }
+    }
+}
+
+impl<A> EventStore<A>
+where
+    A: Aggregate + Clone,
+    A::Event: Clone,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append `events` to the aggregate's log and return their envelopes.
+    ///
+    /// `expected_version` is the version the events were decided against
+    /// (the log's length, 0 for a new aggregate); if others have been
+    /// appended since, nothing is written and [RepositoryError::Conflict] is
+    /// returned. Each event is wrapped in an [EventEnvelope] whose `sequence`
+    /// continues from the last one recorded for `aggregate_id`.
+    pub async fn append(
+        &self,
+        aggregate_id: Uuid,
+        expected_version: u64,
+        events: Vec<A::Event>,
+    ) -> Result<Vec<EventEnvelope<A::Event>>, RepositoryError> {
+        let mut guard = self.events.lock().unwrap();
+        let log = guard.entry(aggregate_id).or_default();
+        let actual = log.len() as u64;
+        if actual != expected_version {
+            return Err(RepositoryError::Conflict {
+                expected: expected_version,
+                actual,
+            });
+        }
+        let occurred_at = now();
+        let mut appended = Vec::with_capacity(events.len());
+        for event in events {
+            let envelope = EventEnvelope {
+                event_id: Uuid::new_v4(),
+                aggregate_id,
+                sequence: log.len() as u64 + 1,
+                occurred_at,
+                event,
+            };
+            appended.push(envelope.clone());
+            log.push((self.appended.fetch_add(1, Ordering::Relaxed), envelope));
+        }
+        Ok(appended)
+    }
+
+    /// Every event recorded for the aggregate, oldest first.
+    pub async fn load(
+        &self,
+        aggregate_id: Uuid,
+    ) -> Result<Vec<EventEnvelope<A::Event>>, RepositoryError> {
+        let guard = self.events.lock().unwrap();
+        let log = guard.get(&aggregate_id).into_iter().flatten();
+        Ok(log.map(|(_, e)| e.clone()).collect())
+    }
+
+    /// Every event recorded for any aggregate, in the order they were
+    /// appended, for building read models across aggregates. The order
+    /// doesn't depend on `occurred_at`, so clocks that tie or step back can't
+    /// reorder it.
+    pub async fn load_all(&self) -> Result<Vec<EventEnvelope<A::Event>>, RepositoryError> {
+        let guard = self.events.lock().unwrap();
+        let mut all: Vec<_> = guard.values().flatten().collect();
+        all.sort_by_key(|(n, _)| *n);
+        Ok(all.into_iter().map(|(_, e)| e.clone()).collect())
+    }
+
+    /// Store `snapshot` as the aggregate's latest snapshot.
+    pub async fn save_snapshot(
+        &self,
+        aggregate_id: Uuid,
+        snapshot: Snapshot<A>,
+    ) -> Result<(), RepositoryError> {
+        let mut guard = self.snapshots.lock().unwrap();
+        guard.insert(aggregate_id, snapshot);
+        Ok(())
+    }
+
+    /// The aggregate's latest snapshot (or `None` if none was saved).
+    pub async fn load_snapshot(
+        &self,
+        aggregate_id: Uuid,
+    ) -> Result<Option<Snapshot<A>>, RepositoryError> {
+        let guard = self.snapshots.lock().unwrap();
+        Ok(guard.get(&aggregate_id).cloned())
+    }
+}
+
+/// Somewhere an aggregate's recorded events can be read back from, e.g. an
+/// [EventStore] or a [SqliteEventLog](crate::db::SqliteEventLog).
+#[async_trait]
+pub trait EventLog<E> {
+    /// Every event recorded for the aggregate, oldest first.
+    async fn history(&self, aggregate_id: Uuid) -> Result<Vec<EventEnvelope<E>>, RepositoryError>;
+}
+
+#[async_trait]
+impl<A> EventLog<A::Event> for EventStore<A>
+where
+    A: Aggregate + Clone + Send + Sync,
+    A::Event: Clone + Send + Sync,
+{
+    async fn history(
+        &self,
+        aggregate_id: Uuid,
+    ) -> Result<Vec<EventEnvelope<A::Event>>, RepositoryError> {
+        self.load(aggregate_id).await
+    }
+}
+
+/// The aggregate's whole log, oldest first, as pretty-printed JSON (an array
+/// of [EventEnvelope]s). An aggregate with no events is an error rather than
+/// an empty array, as that almost always means a mistyped id.
+pub async fn export_history<E: Serialize>(
+    log: &(impl EventLog<E> + ?Sized),
+    aggregate_id: Uuid,
+) -> Result<String> {
+    let log = log.history(aggregate_id).await?;
+    if log.is_empty() {
+        bail!("no events recorded for {aggregate_id}");
+    }
+    Ok(serde_json::to_string_pretty(&log)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::{replay, AggregateError, Repository, RepositoryId};
+    use std::collections::BTreeSet;
+    use stowr_macro::{command, domain, domain_impl};
+
+    #[domain]
+    struct Counter {
+        total: u32,
+    }
+
+    #[domain_impl]
+    impl Counter {
+        #[command]
+        fn add(&mut self, n: u32) {
+            self.total += n;
+        }
+    }
+
+    impl Default for Counter {
+        fn default() -> Self {
+            Counter::new(CounterId::new(), 0u32)
+        }
+    }
+
+    fn adds(ns: &[u32]) -> Vec<CounterEvent> {
+        ns.iter().map(|&n| CounterEvent::Add { n }).collect()
+    }
+
+    fn sequences(log: &[EventEnvelope<CounterEvent>]) -> Vec<u64> {
+        log.iter().map(|e| e.sequence).collect()
+    }
+
+    async fn events(store: &EventStore<Counter>, id: Uuid) -> Vec<CounterEvent> {
+        let envelopes = store.load(id).await.unwrap();
+        envelopes.into_iter().map(|e| e.event).collect()
+    }
+
+    #[tokio::test]
+    async fn append_and_load_keep_order_per_aggregate() {
+        let store = EventStore::<Counter>::new();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        let appended = store.append(a, 0, adds(&[1, 2])).await.unwrap();
+        assert_eq!(sequences(&appended), vec![1, 2]);
+        let appended = store.append(b, 0, adds(&[5])).await.unwrap();
+        assert_eq!(sequences(&appended), vec![1]);
+        let appended = store.append(a, 2, adds(&[3])).await.unwrap();
+        assert_eq!(sequences(&appended), vec![3]);
+
+        let totals: Vec<u32> = events(&store, a)
+            .await
+            .into_iter()
+            .map(|CounterEvent::Add { n }| n)
+            .collect();
+        assert_eq!(totals, vec![1, 2, 3]);
+        assert!(store.load(Uuid::new_v4()).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn snapshot_then_replay_matches_full_replay() {
+        let store = EventStore::<Counter>::new();
+        let id = Uuid::new_v4();
+
+        let version = store.append(id, 0, adds(&[1, 2, 3])).await.unwrap().len() as u64;
+        let state: Counter = replay(None, &events(&store, id).await).unwrap();
+        store
+            .save_snapshot(id, Snapshot::new(version, state))
+            .await
+            .unwrap();
+
+        store.append(id, version, adds(&[10, 20])).await.unwrap();
+        let events = events(&store, id).await;
+        let snapshot = store.load_snapshot(id).await.unwrap();
+        assert_eq!(snapshot.as_ref().map(|s| s.version), Some(3));
+
+        let from_snapshot: Counter = replay(snapshot, &events).unwrap();
+        let full: Counter = replay(None, &events).unwrap();
+        assert_eq!(from_snapshot.total, 36);
+        assert_eq!(from_snapshot.total, full.total);
+    }
+
+    #[tokio::test]
+    async fn sequences_are_contiguous_per_aggregate() {
+        let store = EventStore::<Counter>::new();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        store.append(a, 0, adds(&[1, 1])).await.unwrap();
+        store.append(b, 0, adds(&[1])).await.unwrap();
+        store.append(a, 2, adds(&[1, 1, 1])).await.unwrap();
+        store.append(b, 1, adds(&[1])).await.unwrap();
+
+        let log = store.load(a).await.unwrap();
+        assert_eq!(sequences(&log), vec![1, 2, 3, 4, 5]);
+        assert!(log.iter().all(|e| e.aggregate_id == a));
+        let ids: BTreeSet<Uuid> = log.iter().map(|e| e.event_id).collect();
+        assert_eq!(ids.len(), log.len());
+
+        assert_eq!(sequences(&store.load(b).await.unwrap()), vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn load_all_spans_aggregates_in_order() {
+        let store = EventStore::<Counter>::new();
+        let (a, b) = (Uuid::new_v4(), Uuid::new_v4());
+        store.append(a, 0, adds(&[1, 2])).await.unwrap();
+        store.append(b, 0, adds(&[3])).await.unwrap();
+        store.append(a, 2, adds(&[4])).await.unwrap();
+
+        let all = store.load_all().await.unwrap();
+        let appended: Vec<(Uuid, u64)> = all.iter().map(|e| (e.aggregate_id, e.sequence)).collect();
+        assert_eq!(appended, vec![(a, 1), (a, 2), (b, 1), (a, 3)]);
+    }
+
+    #[tokio::test]
+    async fn append_at_the_current_version_succeeds() {
+        let store = EventStore::<Counter>::new();
+        let id = Uuid::new_v4();
+        let version = store.append(id, 0, adds(&[1])).await.unwrap().len() as u64;
+        let appended = store.append(id, version, adds(&[2, 3])).await.unwrap();
+        assert_eq!(sequences(&appended), vec![2, 3]);
+        let ids = |log: &[EventEnvelope<CounterEvent>]| -> Vec<Uuid> {
+            log.iter().map(|e| e.event_id).collect()
+        };
+        assert_eq!(ids(&store.load(id).await.unwrap()[1..]), ids(&appended));
+    }
+
+    #[tokio::test]
+    async fn stale_appends_conflict_without_writing() {
+        let store = EventStore::<Counter>::new();
+        let id = Uuid::new_v4();
+        store.append(id, 0, adds(&[1, 2])).await.unwrap();
+
+        for stale in [0, 1, 3] {
+            assert!(matches!(
+                store.append(id, stale, adds(&[9])).await,
+                Err(RepositoryError::Conflict { expected, actual: 2 }) if expected == stale
+            ));
+        }
+        let totals: Vec<u32> = events(&store, id)
+            .await
+            .into_iter()
+            .map(|CounterEvent::Add { n }| n)
+            .collect();
+        assert_eq!(totals, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn exported_history_round_trips() {
+        let store = EventStore::<Counter>::new();
+        let id = Uuid::new_v4();
+        store.append(id, 0, adds(&[1, 2])).await.unwrap();
+        store.append(id, 2, adds(&[3])).await.unwrap();
+
+        let json = export_history(&store, id).await.unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert!(value[0]["occurred_at"].as_str().unwrap().ends_with('Z'));
+        let exported: Vec<EventEnvelope<CounterEvent>> = serde_json::from_str(&json).unwrap();
+        let log = store.load(id).await.unwrap();
+        assert_eq!(exported.len(), 3);
+        for (back, original) in exported.iter().zip(&log) {
+            assert_eq!(back.event_id, original.event_id);
+            assert_eq!(back.aggregate_id, id);
+            assert_eq!(back.sequence, original.sequence);
+            assert_eq!(back.occurred_at, original.occurred_at);
+            let (CounterEvent::Add { n: a }, CounterEvent::Add { n: b }) =
+                (&back.event, &original.event);
+            assert_eq!(a, b);
+        }
+    }
+
+    #[tokio::test]
+    async fn exporting_a_missing_aggregate_says_so() {
+        let store = EventStore::<Counter>::new();
+        let id = Uuid::new_v4();
+        let err = export_history(&store, id).await.unwrap_err();
+        assert_eq!(err.to_string(), format!("no events recorded for {id}"));
+    }
+
+    #[tokio::test]
+    async fn missing_snapshot_is_none() {
+        let store = EventStore::<Counter>::new();
+        assert_eq!(store.load_snapshot(Uuid::new_v4()).await.unwrap(), None);
+    }
+
+    #[test]
+    fn snapshot_with_no_later_events_is_returned_as_is() {
+        let state = Counter::new(CounterId::new(), 7u32);
+        let replayed: Result<Counter, AggregateError> =
+            replay(Some(Snapshot::new(4, state.clone())), &adds(&[1, 1, 1, 1]));
+        assert_eq!(replayed.unwrap(), state);
+    }
+}
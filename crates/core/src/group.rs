@@ -1 +1,83 @@
+use serde::{Deserialize, Serialize};
+use stowr_macro::{command, domain, domain_impl};
+use uuid::Uuid;
 
+use crate::asset::AssetId;
+use crate::common::{Aggregate, Identifiable, Named, Repository, RepositoryId};
+
+/// A named collection of assets, e.g. a toolkit or a kit for a job.
+#[domain]
+pub struct Group {
+    name: String,
+    members: Vec<AssetId>,
+}
+
+#[domain_impl]
+impl Group {
+    /// Add `asset` to the group; adding an existing member changes nothing.
+    #[command]
+    pub fn add_member(&mut self, asset: AssetId) {
+        if !self.members.contains(&asset) {
+            self.members.push(asset);
+        }
+    }
+
+    /// Remove `asset` from the group if it is a member.
+    #[command]
+    pub fn remove_member(&mut self, asset: AssetId) {
+        self.members.retain(|m| m != &asset);
+    }
+}
+
+impl Identifiable for Group {
+    type Id = GroupId;
+
+    fn id(&self) -> Uuid {
+        self.id.clone().into()
+    }
+}
+
+impl Named for Group {
+    fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+#[test]
+fn adds_and_removes_members() {
+    let mut group = Group::new(GroupId::new(), "Toolkit", Vec::new());
+    let (drill, saw) = (AssetId(Uuid::new_v4()), AssetId(Uuid::new_v4()));
+
+    for asset in [drill.clone(), saw.clone(), drill.clone()] {
+        for evt in group
+            .handle_command(GroupCommand::AddMember { asset })
+            .unwrap()
+        {
+            group.apply_event(&evt).unwrap();
+        }
+    }
+    assert_eq!(group.members, vec![drill.clone(), saw.clone()]);
+
+    let events = group
+        .handle_command(GroupCommand::RemoveMember {
+            asset: drill.clone(),
+        })
+        .unwrap();
+    assert!(matches!(&events[..], [GroupEvent::RemoveMember { asset }] if *asset == drill));
+    group.apply_event(&events[0]).unwrap();
+    assert_eq!(group.members, vec![saw]);
+}
+
+#[tokio::test]
+async fn group_repository_round_trip() {
+    async fn store(repo: &impl GroupRepository, group: Group) {
+        repo.create(group).await.unwrap();
+    }
+
+    let repo = crate::db::InMemoryRepository::<Group>::new();
+    let id = GroupId::new();
+    let group = Group::new(id.clone(), "Toolkit", vec![AssetId(Uuid::new_v4())]);
+    store(&repo, group.clone()).await;
+
+    assert_eq!(repo.fetch(id).await.unwrap(), Some(group));
+}
@@ -1,15 +1,33 @@
+// `#[domain]` names the registry as `::stowr_core::...`, which needs to
+// resolve in this crate as well as downstream.
+extern crate self as stowr_core;
+
 mod common;
 
-pub use common::{Aggregate, AggregateError};
+#[doc(hidden)]
+pub use inventory;
+
+pub use common::{
+    from_events, now, registered_domains, replay, Aggregate, AggregateError, DomainInfo, FieldInfo,
+    Identifiable, MergeableEntity, Named, NamedRepository, Page, Repository, RepositoryId,
+    SoftDeletable,
+};
 pub mod asset;
 pub mod auth;
+pub mod bus;
 pub mod db;
 pub mod error;
+pub mod eventstore;
 pub mod group;
 pub mod location;
 pub mod logger;
+pub mod projection;
 pub mod tag;
 pub mod user;
+pub mod validation;
 
-pub use asset::Asset;
+pub use asset::{Asset, Query};
+pub use group::Group;
 pub use location::Location;
+pub use tag::Tag;
+pub use user::User;
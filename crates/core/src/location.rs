@@ -1,38 +1,273 @@
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    time::{SystemTime, UNIX_EPOCH},
+};
+use uuid::Uuid;
 
-use crate::common::{Repository, RepositoryId};
+use crate::common::{
+    unix_epoch, DomainInfo, FieldInfo, Identifiable, Named, Repository, RepositoryId, SoftDeletable,
+};
+use crate::error::RepositoryError;
+use crate::validation::Name;
 
 /// Persistence abstraction for [`Location`] data.
 ///
 /// this trait defines the operations required by
 /// the domain logic without committing to any specific database layer.
-pub trait LocationRepository: Repository<Entity = Location, Id = LocationId> {}
+#[async_trait]
+pub trait LocationRepository: Repository<Entity = Location, Id = LocationId> {
+    /// The locations directly under `parent`. The default filters `list`.
+    async fn children(&self, parent: LocationId) -> Result<Vec<Location>, RepositoryError> {
+        let all = self.list().await?;
+        Ok(all
+            .into_iter()
+            .filter(|l| l.parent_id.as_ref() == Some(&parent))
+            .collect())
+    }
+
+    /// The chain of locations from the root down to `id`, inclusive.
+    ///
+    /// Errors with [RepositoryError::NotFound] if `id` doesn't exist, and with
+    /// [RepositoryError::Corrupt] if a parent is missing, parents loop, or the
+    /// chain is deeper than [MAX_LOCATION_DEPTH].
+    async fn ancestry(&self, id: LocationId) -> Result<Vec<Location>, RepositoryError> {
+        let mut chain: Vec<Location> = Vec::new();
+        let mut next = self.fetch(id).await?.ok_or(RepositoryError::NotFound)?;
+        loop {
+            if chain.iter().any(|l| l.id == next.id) {
+                return Err(RepositoryError::Corrupt(format!(
+                    "location {} is its own ancestor",
+                    next.id
+                )));
+            }
+            if chain.len() == MAX_LOCATION_DEPTH {
+                return Err(RepositoryError::Corrupt(format!(
+                    "location nesting exceeds {MAX_LOCATION_DEPTH} levels"
+                )));
+            }
+            let parent = next.parent_id.clone();
+            chain.push(next);
+            let Some(parent) = parent else { break };
+            next = self.fetch(parent.clone()).await?.ok_or_else(|| {
+                RepositoryError::Corrupt(format!("parent location {parent} does not exist"))
+            })?;
+        }
+        chain.reverse();
+        Ok(chain)
+    }
+}
+
+/// The deepest nesting [LocationRepository::ancestry] will follow.
+pub const MAX_LOCATION_DEPTH: usize = 64;
+
+/// Whether making `new_parent` the parent of `child` would put `child` among
+/// its own ancestors.
+///
+/// Walks up from `new_parent`; an already-corrupt loop above it stops the walk
+/// rather than spinning forever.
+pub async fn would_cycle<R>(
+    repo: &R,
+    child: &LocationId,
+    new_parent: &LocationId,
+) -> Result<bool, RepositoryError>
+where
+    R: LocationRepository + ?Sized,
+{
+    let mut seen = HashSet::new();
+    let mut next = Some(new_parent.clone());
+    while let Some(id) = next {
+        if &id == child {
+            return Ok(true);
+        }
+        if !seen.insert(id.clone()) {
+            break;
+        }
+        next = repo.fetch(id).await?.and_then(|l| l.parent_id);
+    }
+    Ok(false)
+}
 
 #[derive(Clone, PartialEq, Eq, Debug)]
 pub enum LocationTag {}
 
 pub type LocationId = RepositoryId<LocationTag>;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "openapi", derive(utoipa::ToSchema))]
 pub struct Location {
     pub id: LocationId,
-    pub name: String,
+    pub name: Name,
+    /// Free-form notes; omitted from JSON when unset.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The location this one is nested in; `None` for a top-level location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<LocationId>,
+    /// Bumped by the repository on every update; see [Identifiable::version].
+    #[serde(default)]
+    pub version: u64,
+    /// Set instead of removing the entity when it is soft-deleted.
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        with = "crate::common::rfc3339::option"
+    )]
+    #[cfg_attr(feature = "openapi", schema(value_type = Option<String>, format = DateTime))]
+    pub deleted_at: Option<SystemTime>,
+    /// When the repository first stored the entity, as RFC 3339.
+    #[serde(default = "unix_epoch", with = "crate::common::rfc3339")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, format = DateTime))]
+    pub created_at: SystemTime,
+    /// When the repository last stored the entity, as RFC 3339.
+    #[serde(default = "unix_epoch", with = "crate::common::rfc3339")]
+    #[cfg_attr(feature = "openapi", schema(value_type = String, format = DateTime))]
+    pub updated_at: SystemTime,
+}
+
+/// Any field values a repository could hand back, timestamps included.
+#[cfg(any(test, feature = "testing"))]
+impl proptest::arbitrary::Arbitrary for Location {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use crate::common::any_time;
+        use proptest::prelude::*;
+        (
+            any::<LocationId>(),
+            any::<Name>(),
+            any::<Option<String>>(),
+            any::<Option<LocationId>>(),
+            any::<u64>(),
+            proptest::option::of(any_time()),
+            any_time(),
+            any_time(),
+        )
+            .prop_map(
+                |(
+                    id,
+                    name,
+                    description,
+                    parent_id,
+                    version,
+                    deleted_at,
+                    created_at,
+                    updated_at,
+                )| Location {
+                    id,
+                    name,
+                    description,
+                    parent_id,
+                    version,
+                    deleted_at,
+                    created_at,
+                    updated_at,
+                },
+            )
+            .boxed()
+    }
 }
 
 impl Location {
-    fn new(id: LocationId, name: impl Into<String>) -> Location {
+    pub fn new(id: LocationId, name: Name, description: Option<impl Into<String>>) -> Location {
         Location {
             id,
-            name: name.into(),
+            name,
+            description: description.map(Into::into),
+            parent_id: None,
+            version: 0,
+            deleted_at: None,
+            created_at: UNIX_EPOCH,
+            updated_at: UNIX_EPOCH,
         }
     }
 }
 
+// Location isn't a `#[domain]` struct, so it registers itself by hand; a test
+// in `common` checks the list against the fields Location serializes
+inventory::submit! {
+    DomainInfo {
+        name: "Location",
+        fields: &[
+            FieldInfo { name: "id", ty: "LocationId" },
+            FieldInfo { name: "name", ty: "Name" },
+            FieldInfo { name: "description", ty: "Option<String>" },
+            FieldInfo { name: "parent_id", ty: "Option<LocationId>" },
+            FieldInfo { name: "version", ty: "u64" },
+            FieldInfo { name: "deleted_at", ty: "Option<SystemTime>" },
+            FieldInfo { name: "created_at", ty: "SystemTime" },
+            FieldInfo { name: "updated_at", ty: "SystemTime" },
+        ],
+    }
+}
+
+impl Identifiable for Location {
+    type Id = LocationId;
+
+    fn id(&self) -> Uuid {
+        self.id.clone().into()
+    }
+
+    fn version(&self) -> Option<u64> {
+        Some(self.version)
+    }
+
+    fn set_version(&mut self, version: u64) {
+        self.version = version;
+    }
+
+    fn created_at(&self) -> Option<SystemTime> {
+        Some(self.created_at)
+    }
+
+    fn set_timestamps(&mut self, created_at: SystemTime, updated_at: SystemTime) {
+        self.created_at = created_at;
+        self.updated_at = updated_at;
+    }
+}
+
+impl Named for Location {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+impl SoftDeletable for Location {
+    fn deleted_at(&self) -> Option<SystemTime> {
+        self.deleted_at
+    }
+
+    fn set_deleted_at(&mut self, at: Option<SystemTime>) {
+        self.deleted_at = at;
+    }
+}
+
 #[test]
 fn creates_location() {
     let id = LocationId::new();
     let name = "warehouse";
-    let loc = Location::new(id.clone(), name);
+    let loc = Location::new(id.clone(), name.parse().unwrap(), None::<String>);
     assert_eq!(loc.id, id);
     assert_eq!(loc.name, "warehouse");
+    assert_eq!(loc.description, None);
+}
+
+#[test]
+fn location_description_round_trips() {
+    let loc = Location::new(
+        LocationId::new(),
+        "Shed".parse().unwrap(),
+        Some("behind the house"),
+    );
+    let json = serde_json::to_string(&loc).unwrap();
+    let back: Location = serde_json::from_str(&json).unwrap();
+    assert_eq!(back.description.as_deref(), Some("behind the house"));
+
+    let bare = Location::new(LocationId::new(), "Shed".parse().unwrap(), None::<String>);
+    let json = serde_json::to_value(&bare).unwrap();
+    assert!(json.get("description").is_none());
+    let back: Location = serde_json::from_value(json).unwrap();
+    assert_eq!(back.description, None);
 }
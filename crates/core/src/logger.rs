@@ -1,3 +1,20 @@
-pub fn init() {
-    // placeholder for logger initialization
+pub use tracing_subscriber::filter::LevelFilter;
+
+/// Install a human-readable subscriber on stderr that logs at `level` and
+/// above. Only the first call (of this or [with_json]) takes effect; later
+/// calls are no-ops.
+pub fn init(level: LevelFilter) {
+    let _ = tracing_subscriber::fmt()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .try_init();
+}
+
+/// Like [init], but logs one JSON object per line for machine consumption.
+pub fn with_json(level: LevelFilter) {
+    let _ = tracing_subscriber::fmt()
+        .json()
+        .with_max_level(level)
+        .with_writer(std::io::stderr)
+        .try_init();
 }
@@ -0,0 +1,155 @@
+use std::collections::HashMap;
+
+use crate::asset::{AssetEvent, AssetId, Quantity};
+use crate::common::Aggregate;
+use crate::eventstore::{EventEnvelope, EventStore};
+use crate::validation::Name;
+
+/// A read model built by folding over recorded events.
+pub trait Projection<E> {
+    fn handle(&mut self, env: &EventEnvelope<E>);
+}
+
+/// Feed every event in `store`, oldest first, through `projection`.
+pub async fn project<A, P>(store: &EventStore<A>, projection: &mut P) -> anyhow::Result<()>
+where
+    A: Aggregate + Clone,
+    A::Event: Clone,
+    P: Projection<A::Event>,
+{
+    for env in store.load_all().await? {
+        projection.handle(&env);
+    }
+    Ok(())
+}
+
+/// What [AssetSummaries] knows about one asset.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetSummary {
+    /// The asset's current name.
+    pub name: Name,
+    /// How many of the asset are on hand.
+    pub quantity: Quantity,
+}
+
+/// The current name and quantity of every asset whose creation is recorded.
+/// Events for an asset with no `Created` event before them are skipped, as
+/// there is nothing to apply them to.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetSummaries(pub HashMap<AssetId, AssetSummary>);
+
+impl AssetSummary {
+    fn apply(&mut self, event: &AssetEvent) {
+        match event {
+            AssetEvent::Created { name, quantity } => {
+                self.name = name.clone();
+                self.quantity = *quantity;
+            }
+            AssetEvent::Renamed { name } => self.name = name.clone(),
+            AssetEvent::QuantityAdjusted { delta } => self.adjust(*delta),
+            AssetEvent::Received { n } => self.adjust(i64::from(*n)),
+            AssetEvent::Issued { n } => self.adjust(-i64::from(*n)),
+            AssetEvent::MovedTo { .. } => {}
+        }
+    }
+
+    /// The aggregate only records changes that keep the quantity in range,
+    /// so the clamp is never expected to bite.
+    fn adjust(&mut self, delta: i64) {
+        let on_hand = i64::from(self.quantity.get()).saturating_add(delta);
+        self.quantity = Quantity::new(on_hand.clamp(0, u32::MAX.into()) as u32);
+    }
+}
+
+impl Projection<AssetEvent> for AssetSummaries {
+    fn handle(&mut self, env: &EventEnvelope<AssetEvent>) {
+        let id = AssetId(env.aggregate_id);
+        match (&env.event, self.0.get_mut(&id)) {
+            (_, Some(summary)) => summary.apply(&env.event),
+            (AssetEvent::Created { name, quantity }, None) => {
+                let (name, quantity) = (name.clone(), *quantity);
+                self.0.insert(id, AssetSummary { name, quantity });
+            }
+            (_, None) => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::asset::Asset;
+    use crate::location::LocationId;
+    use uuid::Uuid;
+
+    #[tokio::test]
+    async fn asset_summaries_track_name_and_quantity() {
+        let store = EventStore::<Asset>::new();
+        let (drill, tape, untracked) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let name = |s: &str| s.parse::<Name>().unwrap();
+        let created = |s: &str, n: u32| AssetEvent::Created {
+            name: name(s),
+            quantity: Quantity::new(n),
+        };
+        store
+            .append(
+                drill,
+                0,
+                vec![
+                    created("Drill", 2),
+                    AssetEvent::QuantityAdjusted { delta: 3 },
+                ],
+            )
+            .await
+            .unwrap();
+        store
+            .append(tape, 0, vec![created("Tape", 5)])
+            .await
+            .unwrap();
+        store
+            .append(
+                untracked,
+                0,
+                vec![AssetEvent::QuantityAdjusted { delta: 1 }],
+            )
+            .await
+            .unwrap();
+        store
+            .append(
+                drill,
+                2,
+                vec![
+                    AssetEvent::MovedTo {
+                        location_id: LocationId::new(),
+                    },
+                    AssetEvent::QuantityAdjusted { delta: -1 },
+                    AssetEvent::Received { n: 4 },
+                    AssetEvent::Issued { n: 3 },
+                    AssetEvent::Renamed {
+                        name: name("Cordless drill"),
+                    },
+                ],
+            )
+            .await
+            .unwrap();
+
+        let mut summaries = AssetSummaries::default();
+        project(&store, &mut summaries).await.unwrap();
+
+        assert_eq!(summaries.0.len(), 2);
+        assert_eq!(
+            summaries.0[&AssetId(drill)],
+            AssetSummary {
+                name: name("Cordless drill"),
+                quantity: Quantity::new(5),
+            }
+        );
+        assert_eq!(
+            summaries.0[&AssetId(tape)],
+            AssetSummary {
+                name: name("Tape"),
+                quantity: Quantity::new(5),
+            }
+        );
+    }
+}
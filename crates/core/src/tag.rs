@@ -1 +1,156 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::{fmt, str::FromStr};
+use stowr_macro::domain;
+use uuid::Uuid;
 
+use crate::asset::AssetId;
+use crate::common::{Identifiable, Named, Repository, RepositoryId};
+use crate::error::{RepositoryError, TagError};
+use crate::validation::Name;
+
+/// A label that can be attached to any number of assets.
+///
+/// The name and color are checked when built and when deserialized, so a
+/// repository can't be handed a blank name or a malformed color.
+#[domain]
+pub struct Tag {
+    name: Name,
+    color: Option<Color>,
+}
+
+impl Tag {
+    /// Like `new`, but from plain strings, rejecting a blank name or a color
+    /// that isn't `#rrggbb`.
+    pub fn try_new(
+        id: TagId,
+        name: impl Into<String>,
+        color: Option<impl Into<String>>,
+    ) -> Result<Tag, TagError> {
+        let name = Name::new(name)?;
+        let color = color.map(|c| Color::new(c)).transpose()?;
+        Ok(Tag::new(id, name, color))
+    }
+}
+
+/// A CSS-style `#rrggbb` tag color, in either case.
+///
+/// Serialized as a plain string, and checked again when deserialized.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Color(String);
+
+impl Color {
+    pub fn new(color: impl Into<String>) -> Result<Color, TagError> {
+        let color = color.into();
+        let is_hex = color
+            .strip_prefix('#')
+            .is_some_and(|hex| hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit()));
+        if is_hex {
+            Ok(Color(color))
+        } else {
+            Err(TagError::InvalidColor(color))
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Color {
+    type Err = TagError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Color::new(s)
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = TagError;
+
+    fn try_from(color: String) -> Result<Self, Self::Error> {
+        Color::new(color)
+    }
+}
+
+impl From<Color> for String {
+    fn from(color: Color) -> Self {
+        color.0
+    }
+}
+
+impl Identifiable for Tag {
+    type Id = TagId;
+
+    fn id(&self) -> Uuid {
+        self.id.clone().into()
+    }
+}
+
+impl Named for Tag {
+    fn name(&self) -> &str {
+        self.name.as_str()
+    }
+}
+
+/// The many-to-many association between assets and tags.
+#[async_trait]
+pub trait TaggingRepository {
+    /// Attach `tag` to `asset`; tagging twice is not an error.
+    async fn tag(&self, asset: AssetId, tag: TagId) -> Result<(), RepositoryError>;
+
+    /// Detach `tag` from `asset` (or [RepositoryError::NotFound] if it wasn't
+    /// attached).
+    async fn untag(&self, asset: AssetId, tag: TagId) -> Result<(), RepositoryError>;
+
+    /// Every tag attached to `asset`.
+    async fn tags_of(&self, asset: AssetId) -> Result<Vec<TagId>, RepositoryError>;
+
+    /// Every asset carrying `tag`.
+    async fn assets_with_tag(&self, tag: TagId) -> Result<Vec<AssetId>, RepositoryError>;
+}
+
+#[test]
+fn validates_name_and_color() {
+    assert!(Tag::try_new(TagId::new(), "fragile", Some("#ff8800")).is_ok());
+    assert!(Tag::try_new(TagId::new(), "fragile", Some("#FF88aa")).is_ok());
+    assert!(Tag::try_new(TagId::new(), "fragile", None::<String>).is_ok());
+    assert_eq!(
+        Tag::try_new(TagId::new(), "  ", None::<String>).unwrap_err(),
+        TagError::Name(crate::error::ValidationError::EmptyName)
+    );
+    for bad in ["red", "#ff880", "ff8800", "#ff880g", "#ff88001"] {
+        assert_eq!(
+            Tag::try_new(TagId::new(), "fragile", Some(bad)).unwrap_err(),
+            TagError::InvalidColor(bad.into())
+        );
+    }
+}
+
+#[tokio::test]
+async fn invalid_tags_are_rejected_before_a_repository_sees_them() {
+    use crate::{db::InMemoryRepository, Repository};
+    use serde_json::json;
+
+    let repo = InMemoryRepository::<Tag>::new();
+    let tag = json!({ "id": TagId::new(), "name": "fragile", "color": "#ff8800" });
+    repo.create(serde_json::from_value(tag).unwrap())
+        .await
+        .unwrap();
+
+    for (name, color, reason) in [("", "#ff8800", "empty"), ("fragile", "red", "\"red\"")] {
+        let tag = json!({ "id": TagId::new(), "name": name, "color": color });
+        let err = serde_json::from_value::<Tag>(tag).unwrap_err().to_string();
+        assert!(err.contains(reason), "{err}");
+    }
+    let stored = repo.list().await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].color.as_ref().map(Color::as_str), Some("#ff8800"));
+}
@@ -1 +1,78 @@
+use serde::{Deserialize, Serialize};
+use stowr_macro::domain;
+use uuid::Uuid;
 
+use crate::auth::hash_password;
+use crate::common::{Identifiable, Named, Repository, RepositoryId};
+use crate::error::AuthError;
+
+/// What a [User] is allowed to do; see [authorize](crate::auth::authorize).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Role {
+    /// Everything, including deleting.
+    Admin,
+    /// Create, update and view, but not delete.
+    Editor,
+    /// View only.
+    #[default]
+    Viewer,
+}
+
+/// Someone who can sign in. Only the Argon2 hash of their password is kept.
+#[domain]
+pub struct User {
+    username: String,
+    email: String,
+    password_hash: String,
+    role: Role,
+}
+
+impl User {
+    /// A new user whose `password` is hashed before it is stored.
+    pub fn register(
+        id: UserId,
+        username: impl Into<String>,
+        email: impl Into<String>,
+        password: &str,
+        role: Role,
+    ) -> Result<User, AuthError> {
+        Ok(User::new(
+            id,
+            username,
+            email,
+            hash_password(password)?,
+            role,
+        ))
+    }
+}
+
+impl Identifiable for User {
+    type Id = UserId;
+
+    fn id(&self) -> Uuid {
+        self.id.clone().into()
+    }
+}
+
+impl Named for User {
+    fn name(&self) -> &str {
+        &self.username
+    }
+}
+
+#[test]
+fn register_stores_only_the_hash() {
+    let user = User::register(
+        UserId::new(),
+        "ada",
+        "ada@example.com",
+        "hunter2",
+        Role::Editor,
+    )
+    .unwrap();
+    assert_eq!(user.username, "ada");
+    assert_eq!(user.role, Role::Editor);
+    assert!(!user.password_hash.contains("hunter2"));
+    assert!(crate::auth::verify_password("hunter2", &user.password_hash).unwrap());
+    assert!(!format!("{user:?}").contains("hunter2"));
+}
@@ -0,0 +1,145 @@
+use serde::{Deserialize, Serialize};
+use std::{fmt, ops::Deref, str::FromStr};
+
+use crate::error::ValidationError;
+
+/// The name of an [Asset](crate::Asset) or [Location](crate::Location): not
+/// blank, and at most [Name::MAX_LEN] characters.
+///
+/// Serialized as a plain string, and checked again when deserialized.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Name(String);
+
+impl Name {
+    /// The longest name allowed, in characters.
+    pub const MAX_LEN: usize = 255;
+
+    pub fn new(name: impl Into<String>) -> Result<Name, ValidationError> {
+        let name = name.into();
+        if name.trim().is_empty() {
+            return Err(ValidationError::EmptyName);
+        }
+        let len = name.chars().count();
+        if len > Self::MAX_LEN {
+            return Err(ValidationError::NameTooLong(len));
+        }
+        Ok(Name(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Documented as a string with the lengths [Name::new] accepts.
+#[cfg(feature = "openapi")]
+impl utoipa::PartialSchema for Name {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        use utoipa::openapi::schema::{ObjectBuilder, Type};
+        ObjectBuilder::new()
+            .schema_type(Type::String)
+            .min_length(Some(1))
+            .max_length(Some(Name::MAX_LEN))
+            .into()
+    }
+}
+
+#[cfg(feature = "openapi")]
+impl utoipa::ToSchema for Name {}
+
+/// Names of 1 to [Name::MAX_LEN] printable characters, never blank.
+#[cfg(any(test, feature = "testing"))]
+impl proptest::arbitrary::Arbitrary for Name {
+    type Parameters = ();
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with(_: ()) -> Self::Strategy {
+        use proptest::prelude::*;
+        "[^\\s\\p{C}][^\\p{C}]{0,254}"
+            .prop_map(|name| Name::new(name).expect("the pattern only makes valid names"))
+            .boxed()
+    }
+}
+
+impl fmt::Display for Name {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Name {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Name::new(s)
+    }
+}
+
+impl TryFrom<String> for Name {
+    type Error = ValidationError;
+
+    fn try_from(name: String) -> Result<Self, Self::Error> {
+        Name::new(name)
+    }
+}
+
+impl From<Name> for String {
+    fn from(name: Name) -> Self {
+        name.0
+    }
+}
+
+impl Deref for Name {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<str> for Name {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl PartialEq<str> for Name {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Name {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[test]
+fn rejects_blank_names() {
+    assert_eq!(Name::new("").unwrap_err(), ValidationError::EmptyName);
+    assert_eq!(Name::new(" \t").unwrap_err(), ValidationError::EmptyName);
+}
+
+#[test]
+fn accepts_names_up_to_the_limit() {
+    let longest = "é".repeat(Name::MAX_LEN);
+    assert_eq!(Name::new(longest.clone()).unwrap().as_str(), longest);
+    assert_eq!(
+        Name::new(longest + "e").unwrap_err(),
+        ValidationError::NameTooLong(256)
+    );
+}
+
+#[test]
+fn parses_displays_and_serializes_as_the_string() {
+    let name: Name = "Cordless drill".parse().unwrap();
+    assert_eq!(name, "Cordless drill");
+    assert_eq!(name.to_string(), "Cordless drill");
+
+    let json = serde_json::to_string(&name).unwrap();
+    assert_eq!(json, r#""Cordless drill""#);
+    assert_eq!(serde_json::from_str::<Name>(&json).unwrap(), name);
+    assert!(serde_json::from_str::<Name>(r#""""#).is_err());
+}
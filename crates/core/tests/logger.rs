@@ -0,0 +1,12 @@
+// `init` installs the global subscriber, so this runs in its own test binary
+// rather than next to the unit tests that install theirs with `traced_test`.
+
+use stowr_core::logger::{init, with_json, LevelFilter};
+
+#[test]
+fn init_is_idempotent() {
+    init(LevelFilter::DEBUG);
+    init(LevelFilter::TRACE);
+    with_json(LevelFilter::INFO);
+    tracing::info!("still logging after repeated init");
+}
@@ -0,0 +1,188 @@
+#![cfg(feature = "sqlite")]
+
+use std::time::{Duration, UNIX_EPOCH};
+use stowr_core::{
+    asset::{Asset, AssetEvent, AssetId},
+    db::{SqliteEventLog, SqliteRepository},
+    error::RepositoryError,
+    eventstore::export_history,
+    Page, Repository,
+};
+use uuid::Uuid;
+
+async fn repo() -> SqliteRepository<Asset, AssetId> {
+    SqliteRepository::connect("sqlite::memory:").await.unwrap()
+}
+
+fn asset(name: &str) -> Asset {
+    Asset::new(
+        AssetId(Uuid::new_v4()),
+        name.parse().unwrap(),
+        None::<String>,
+        1u32,
+    )
+}
+
+#[tokio::test]
+async fn creates_and_fetches_asset() {
+    let repo = repo().await;
+    let drill = asset("drill");
+    repo.create(drill.clone()).await.unwrap();
+
+    let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+    assert_eq!(fetched.id, drill.id);
+    assert_eq!(fetched.name, "drill");
+    assert!(repo.fetch(AssetId(Uuid::new_v4())).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn duplicate_id_already_exists() {
+    let repo = repo().await;
+    let drill = asset("drill");
+    repo.create(drill.clone()).await.unwrap();
+    assert!(matches!(
+        repo.create(drill).await,
+        Err(RepositoryError::AlreadyExists)
+    ));
+}
+
+#[tokio::test]
+async fn updates_asset() {
+    let repo = repo().await;
+    let mut drill = asset("drill");
+    repo.create(drill.clone()).await.unwrap();
+
+    drill.name = "hammer drill".parse().unwrap();
+    repo.update(drill.clone()).await.unwrap();
+    let fetched = repo.fetch(drill.id.clone()).await.unwrap().unwrap();
+    assert_eq!(fetched.name, "hammer drill");
+
+    assert!(matches!(
+        repo.update(asset("saw")).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+#[tokio::test]
+async fn stale_updates_conflict() {
+    let repo = repo().await;
+    let drill = asset("drill");
+    repo.create(drill.clone()).await.unwrap();
+
+    let updated = repo.update(drill.clone()).await.unwrap();
+    assert_eq!(updated.version, 1);
+    assert_eq!(
+        repo.fetch(drill.id.clone()).await.unwrap().unwrap().version,
+        1
+    );
+    assert!(matches!(
+        repo.update(drill).await,
+        Err(RepositoryError::Conflict {
+            expected: 0,
+            actual: 1
+        })
+    ));
+}
+
+#[tokio::test]
+async fn updates_advance_only_updated_at() {
+    let repo = repo().await;
+    let created = repo.create(asset("drill")).await.unwrap();
+    assert_eq!(created.created_at, created.updated_at);
+    assert!(created.created_at > UNIX_EPOCH);
+
+    tokio::time::sleep(Duration::from_millis(5)).await;
+    let mut stale = created.clone();
+    stale.created_at = UNIX_EPOCH;
+    let updated = repo.update(stale).await.unwrap();
+    assert_eq!(updated.created_at, created.created_at);
+    assert!(updated.updated_at > created.updated_at);
+    let fetched = repo.fetch(created.id).await.unwrap().unwrap();
+    assert_eq!(fetched.created_at, created.created_at);
+    assert_eq!(fetched.updated_at, updated.updated_at);
+}
+
+#[tokio::test]
+async fn deletes_asset() {
+    let repo = repo().await;
+    let drill = asset("drill");
+    repo.create(drill.clone()).await.unwrap();
+
+    repo.delete(drill.id.clone()).await.unwrap();
+    assert!(repo.fetch(drill.id.clone()).await.unwrap().is_none());
+    assert!(matches!(
+        repo.delete(drill.id).await,
+        Err(RepositoryError::NotFound)
+    ));
+}
+
+#[tokio::test]
+async fn lists_pages_and_counts_in_insertion_order() {
+    let repo = repo().await;
+    let assets: Vec<Asset> = ["a", "b", "c"].into_iter().map(asset).collect();
+    repo.create_many(assets.clone()).await.unwrap();
+
+    let names = |v: Vec<Asset>| v.into_iter().map(|a| a.name).collect::<Vec<_>>();
+    assert_eq!(names(repo.list().await.unwrap()), vec!["a", "b", "c"]);
+    assert_eq!(
+        names(repo.list_page(Page::new(1, 5)).await.unwrap()),
+        vec!["b", "c"]
+    );
+    assert_eq!(repo.count().await.unwrap(), 3);
+
+    let ids = [assets[2].id.clone(), assets[0].id.clone()];
+    assert_eq!(names(repo.fetch_many(&ids).await.unwrap()), vec!["c", "a"]);
+}
+
+#[tokio::test]
+async fn event_log_appends_and_loads_in_sequence() {
+    let log = SqliteEventLog::<AssetEvent>::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let (drill, saw) = (Uuid::new_v4(), Uuid::new_v4());
+    let adjust = |delta| AssetEvent::QuantityAdjusted { delta };
+    assert_eq!(log.append(drill, 0, vec![adjust(2)]).await.unwrap(), 1);
+    assert_eq!(log.append(saw, 0, vec![adjust(5)]).await.unwrap(), 1);
+    assert_eq!(
+        log.append(drill, 1, vec![adjust(-1), adjust(3)])
+            .await
+            .unwrap(),
+        3
+    );
+
+    let events = log.load(drill).await.unwrap();
+    let logged: Vec<_> = events
+        .iter()
+        .map(|e| match e.event {
+            AssetEvent::QuantityAdjusted { delta } => (e.sequence, delta),
+            _ => unreachable!(),
+        })
+        .collect();
+    assert_eq!(logged, vec![(1, 2), (2, -1), (3, 3)]);
+    assert!(events.iter().all(|e| e.aggregate_id == drill));
+    assert!(export_history(&log, drill)
+        .await
+        .unwrap()
+        .contains("QuantityAdjusted"));
+    assert!(log.load(Uuid::new_v4()).await.unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn event_log_rejects_stale_appends() {
+    let log = SqliteEventLog::<AssetEvent>::connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let id = Uuid::new_v4();
+    log.append(id, 0, vec![AssetEvent::Received { n: 1 }])
+        .await
+        .unwrap();
+
+    assert!(matches!(
+        log.append(id, 0, vec![AssetEvent::Received { n: 2 }]).await,
+        Err(RepositoryError::Conflict {
+            expected: 0,
+            actual: 1
+        })
+    ));
+    assert_eq!(log.load(id).await.unwrap().len(), 1);
+}
@@ -1,7 +1,254 @@
+use dioxus::logger::tracing::warn;
 use dioxus::prelude::*;
+use std::{rc::Rc, sync::Arc};
+use stowr_core::{
+    asset::{AssetCommand, AssetId, AssetQueries},
+    db::InMemoryRepository,
+    error::{RepositoryError, ValidationError},
+    location::LocationId,
+    validation::Name,
+    Aggregate, Asset, Location, Repository,
+};
+use uuid::Uuid;
 
-const FAVICON: Asset = asset!("/assets/favicon.ico");
-const MAIN_CSS: Asset = asset!("/assets/main.css");
+mod storage;
+
+use storage::{MemoryStorage, Storage};
+
+const FAVICON: dioxus::prelude::Asset = asset!("/assets/favicon.ico");
+const MAIN_CSS: dioxus::prelude::Asset = asset!("/assets/main.css");
+
+#[derive(Clone, Debug, PartialEq, Routable)]
+#[rustfmt::skip]
+enum Route {
+    #[layout(Shell)]
+        #[redirect("/", || Route::Assets {})]
+        #[route("/locations", LocationsPage)]
+        Locations {},
+        #[route("/assets", AssetsPage)]
+        Assets {},
+        #[route("/settings", SettingsPage)]
+        Settings {},
+}
+
+/// Repositories shared with every component through context, and where
+/// their contents are saved.
+#[derive(Clone)]
+struct Repos {
+    assets: Arc<InMemoryRepository<Asset>>,
+    locations: Arc<InMemoryRepository<Location>>,
+    storage: Rc<dyn Storage>,
+}
+
+impl Repos {
+    fn with_storage(storage: Rc<dyn Storage>) -> Self {
+        Repos {
+            assets: Default::default(),
+            locations: Default::default(),
+            storage,
+        }
+    }
+
+    /// Repositories saved to localStorage on the web, and nowhere elsewhere.
+    fn for_platform() -> Self {
+        #[cfg(target_arch = "wasm32")]
+        return Repos::with_storage(Rc::new(storage::LocalStorage));
+        #[cfg(not(target_arch = "wasm32"))]
+        Repos::default()
+    }
+}
+
+impl Default for Repos {
+    fn default() -> Self {
+        Repos::with_storage(Rc::new(MemoryStorage::default()))
+    }
+}
+
+/// Whether the asset form is showing, and which asset it edits if it isn't
+/// adding a new one.
+#[derive(Clone, Copy)]
+struct AssetFormState {
+    open: Signal<bool>,
+    editing: Signal<Option<AssetId>>,
+}
+
+impl AssetFormState {
+    fn add(mut self) {
+        self.editing.set(None);
+        self.open.set(true);
+    }
+
+    fn edit(mut self, id: AssetId) {
+        self.editing.set(Some(id));
+        self.open.set(true);
+    }
+
+    fn close(mut self) {
+        self.open.set(false);
+        self.editing.set(None);
+    }
+}
+
+/// Provide the state the panels share: the assets currently shown, every
+/// location, the location (if any) the asset table is filtered to, and the
+/// asset form's state.
+fn use_app_state() {
+    use_context_provider(|| Signal::new(Vec::<Asset>::new()));
+    use_context_provider(|| Signal::new(Vec::<Location>::new()));
+    use_context_provider(|| Signal::new(None::<LocationId>));
+    use_context_provider(|| AssetFormState {
+        open: Signal::new(false),
+        editing: Signal::new(None),
+    });
+}
+
+/// What the user has typed into the asset form, before validation.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct AssetDraft {
+    name: String,
+    description: String,
+    quantity: String,
+    /// Version of the asset being edited, so saving over a newer one conflicts.
+    version: u64,
+}
+
+impl AssetDraft {
+    /// Validate the inputs and build the asset they describe under `id`.
+    fn to_asset(&self, id: AssetId) -> Result<Asset, String> {
+        let name = Name::new(self.name.trim()).map_err(|err| match err {
+            ValidationError::EmptyName => "Name is required.".to_string(),
+            ValidationError::NameTooLong(_) => {
+                format!("Name must be at most {} characters.", Name::MAX_LEN)
+            }
+        })?;
+        let quantity: u32 = self
+            .quantity
+            .trim()
+            .parse()
+            .map_err(|_| "Quantity must be a whole number of 0 or more.".to_string())?;
+        let description = Some(self.description.trim()).filter(|d| !d.is_empty());
+        Ok(Asset::new(id, name, description, quantity))
+    }
+}
+
+impl From<&Asset> for AssetDraft {
+    fn from(asset: &Asset) -> Self {
+        AssetDraft {
+            name: asset.name.to_string(),
+            description: asset.description.clone().unwrap_or_default(),
+            quantity: asset.quantity.to_string(),
+            version: asset.version,
+        }
+    }
+}
+
+/// A column the asset table can be sorted by.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum SortKey {
+    Name,
+    Quantity,
+}
+
+/// How the asset table is ordered; with no key, rows stay in repository order.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct SortState {
+    key: Option<SortKey>,
+    descending: bool,
+}
+
+impl SortState {
+    /// The state after clicking `key`'s header: ascending when it is a new
+    /// column, reversed when it is the current one.
+    fn toggle(self, key: SortKey) -> Self {
+        if self.key == Some(key) {
+            SortState {
+                descending: !self.descending,
+                ..self
+            }
+        } else {
+            SortState {
+                key: Some(key),
+                descending: false,
+            }
+        }
+    }
+
+    /// Stably sort `assets`, comparing names case-insensitively.
+    fn sort(self, assets: &mut [Asset]) {
+        let Some(key) = self.key else { return };
+        assets.sort_by(|a, b| {
+            let order = match key {
+                SortKey::Name => a.name.to_lowercase().cmp(&b.name.to_lowercase()),
+                SortKey::Quantity => a.quantity.cmp(&b.quantity),
+            };
+            if self.descending {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+    }
+
+    /// The `aria-sort` value for `key`'s header.
+    fn aria_sort(self, key: SortKey) -> &'static str {
+        match (self.key == Some(key), self.descending) {
+            (false, _) => "none",
+            (true, false) => "ascending",
+            (true, true) => "descending",
+        }
+    }
+}
+
+/// Validate `draft` and store it, as a new asset or over the one `editing`.
+///
+/// Editing keeps the asset's location, which the form doesn't show.
+async fn save_asset(
+    repos: &Repos,
+    draft: &AssetDraft,
+    editing: Option<AssetId>,
+) -> Result<Asset, String> {
+    let saved = match editing {
+        None => {
+            let asset = draft.to_asset(AssetId(Uuid::now_v7()))?;
+            repos.assets.create(asset).await
+        }
+        Some(id) => {
+            let mut asset = draft.to_asset(id.clone())?;
+            asset.version = draft.version;
+            let existing = repos.assets.fetch(id).await;
+            if let Ok(Some(existing)) = existing {
+                asset.location_id = existing.location_id;
+            }
+            repos.assets.update(asset).await
+        }
+    };
+    let saved = saved.map_err(|err| format!("Couldn't save the asset: {err}"))?;
+    repos.persist().await;
+    Ok(saved)
+}
+
+/// Apply [AssetCommand::AdjustQuantity] to the stored asset and save it.
+async fn adjust_quantity(repos: &Repos, id: AssetId, delta: i64) -> Result<Asset, String> {
+    let mut asset = repos
+        .assets
+        .fetch(id)
+        .await
+        .map_err(|err| err.to_string())?
+        .ok_or("asset no longer exists")?;
+    for event in asset
+        .handle_command(AssetCommand::AdjustQuantity { delta })
+        .map_err(|err| err.to_string())?
+    {
+        asset.apply_event(&event).map_err(|err| err.to_string())?;
+    }
+    let updated = repos
+        .assets
+        .update(asset)
+        .await
+        .map_err(|err| err.to_string())?;
+    repos.persist().await;
+    Ok(updated)
+}
 
 fn main() {
     dioxus::launch(App);
@@ -9,12 +256,33 @@ fn main() {
 
 #[component]
 fn App() -> Element {
+    let repos = use_context_provider(Repos::for_platform);
+    use_app_state();
+    // Pages read the repositories as they mount, so fill them first.
+    let restored = use_resource(move || {
+        let repos = repos.clone();
+        async move {
+            if let Err(err) = repos.restore().await {
+                warn!("couldn't restore saved data: {err}");
+            }
+        }
+    });
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
         document::Link { rel: "stylesheet", href: MAIN_CSS }
+        if restored.read().is_some() {
+            Router::<Route> {}
+        }
+    }
+}
+
+/// Chrome shared by every page, with the current route rendered in `main`.
+#[component]
+fn Shell() -> Element {
+    rsx! {
         AppHeader {}
         AppNav {}
-        AppBody {}
+        main { Outlet::<Route> {} }
         AppFooter {}
     }
 }
@@ -29,13 +297,30 @@ fn AppHeader() -> Element {
 }
 
 #[component]
-fn AppBody() -> Element {
-    rsx![
-        main {
-            Locations {}
-            Assets {}
+fn LocationsPage() -> Element {
+    rsx! {
+        Locations {}
+    }
+}
+
+#[component]
+fn AssetsPage() -> Element {
+    rsx! {
+        Locations {}
+        Assets {}
+    }
+}
+
+#[component]
+fn SettingsPage() -> Element {
+    rsx! {
+        section { id: "settings-panel", aria_label: "Settings",
+            header {
+                h2 { "Settings" }
+            }
+            p { "There is nothing to configure yet." }
         }
-    ]
+    }
 }
 
 #[component]
@@ -53,13 +338,28 @@ fn AppNav() -> Element {
         nav { aria_label: "Global",
             ul { class: "global-nav",
                 li {
-                    a { id: "nav-locations", href: "#", "Locations" }
+                    Link {
+                        id: "nav-locations",
+                        to: Route::Locations {},
+                        active_class: "active",
+                        "Locations"
+                    }
                 }
                 li {
-                    a { id: "nav-assets", href: "#", "Assets" }
+                    Link {
+                        id: "nav-assets",
+                        to: Route::Assets {},
+                        active_class: "active",
+                        "Assets"
+                    }
                 }
                 li {
-                    a { id: "nav-settings", href: "#", "Settings" }
+                    Link {
+                        id: "nav-settings",
+                        to: Route::Settings {},
+                        active_class: "active",
+                        "Settings"
+                    }
                 }
             }
         }
@@ -68,6 +368,15 @@ fn AppNav() -> Element {
 
 #[component]
 fn Locations() -> Element {
+    let repos = use_context::<Repos>();
+    let mut locations = use_context::<Signal<Vec<Location>>>();
+    let loaded = use_resource(move || {
+        let repo = repos.locations.clone();
+        async move {
+            locations.set(repo.list().await?);
+            Ok::<_, RepositoryError>(())
+        }
+    });
     rsx! {
         aside { id: "locations-panel", aria_label: "Locations",
             header {
@@ -76,11 +385,22 @@ fn Locations() -> Element {
             }
             nav {
                 ul { id: "location-list",
-                    {(0..5).map(|i| rsx! {
-                        li {
-                            a { href: "#", "Location {i}" }
-                        }
-                    })}
+                    match &*loaded.read() {
+                        None => rsx! {
+                            li { "Loading…" }
+                        },
+                        Some(Err(err)) => rsx! {
+                            li { "Couldn't load locations: {err}" }
+                        },
+                        Some(Ok(())) if locations.read().is_empty() => rsx! {
+                            li { "No locations yet." }
+                        },
+                        Some(Ok(())) => rsx! {
+                            for location in locations.iter() {
+                                LocationItem { key: "{location.id}", location: location.clone() }
+                            }
+                        },
+                    }
                 }
             }
             LocationForm {}
@@ -88,43 +408,274 @@ fn Locations() -> Element {
     }
 }
 
+/// A location in the side panel; clicking it filters the asset table to it,
+/// or back to all locations if it was already selected.
+#[component]
+fn LocationItem(location: Location) -> Element {
+    let mut selected = use_context::<Signal<Option<LocationId>>>();
+    let is_selected = selected.read().as_ref() == Some(&location.id);
+    let id = location.id.clone();
+    rsx! {
+        li {
+            a {
+                href: "#",
+                "data-location-id": "{location.id}",
+                class: if is_selected { "selected" },
+                "aria-current": if is_selected { "true" },
+                onclick: move |evt| {
+                    evt.prevent_default();
+                    let next = (!is_selected).then(|| id.clone());
+                    selected.set(next);
+                },
+                "{location.name}"
+            }
+        }
+    }
+}
+
 #[component]
 fn Assets() -> Element {
+    let repos = use_context::<Repos>();
+    let mut assets = use_context::<Signal<Vec<Asset>>>();
+    let locations = use_context::<Signal<Vec<Location>>>();
+    let selected = use_context::<Signal<Option<LocationId>>>();
+    let form = use_context::<AssetFormState>();
+    let sort = use_signal(SortState::default);
+    let loaded = use_resource(move || {
+        let repo = repos.assets.clone();
+        let selected = selected();
+        async move {
+            let shown = match selected {
+                Some(location) => repo.by_location(location).await?,
+                None => repo.list().await?,
+            };
+            assets.set(shown);
+            Ok::<_, RepositoryError>(())
+        }
+    });
+    let location_name = selected
+        .read()
+        .as_ref()
+        .and_then(|id| {
+            locations
+                .iter()
+                .find(|l| &l.id == id)
+                .map(|l| l.name.to_string())
+        })
+        .unwrap_or_else(|| "All Locations".into());
+    let mut shown = assets();
+    sort().sort(&mut shown);
     rsx! {
         section { id: "assets-panel", aria_label: "Assets",
             header {
                 h2 {
                     "Assets in "
-                    span { id: "selected-location-name", "All Locations" }
+                    span { id: "selected-location-name", "{location_name}" }
                 }
-                button { id: "btn-add-asset", "+ Add Asset" }
+                button { id: "btn-add-asset", onclick: move |_| form.add(), "+ Add Asset" }
             }
             article {
                 table { id: "asset-table",
                     thead {
                         tr {
-                            th { "Name" }
+                            SortableHeader { label: "Name", by: SortKey::Name, sort }
                             th { "Description" }
-                            th { "Quantity" }
+                            SortableHeader { label: "Quantity", by: SortKey::Quantity, sort }
                             th { "Actions" }
                         }
                     }
                     tbody {
-                        {(0..5).map(|i| rsx! {
-                            tr {
-                                td {
-                                    a { href: "#", "Asset {i}" }
+                        match &*loaded.read() {
+                            None => rsx! {
+                                tr {
+                                    td { colspan: "4", "Loading…" }
                                 }
-                                td { "This is asset {i}" }
-                                td { "{i * 2}" }
-                                td { "[+] | [-]" }
-                            }
-                        })}
+                            },
+                            Some(Err(err)) => rsx! {
+                                tr {
+                                    td { colspan: "4", "Couldn't load assets: {err}" }
+                                }
+                            },
+                            Some(Ok(())) if assets.read().is_empty() => rsx! {
+                                tr {
+                                    td { colspan: "4", "No assets yet." }
+                                }
+                            },
+                            Some(Ok(())) => rsx! {
+                                for asset in shown {
+                                    AssetRow { key: "{asset.id}", asset }
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+        if (form.open)() {
+            AssetForm {}
+        }
+    }
+}
+
+/// A column header that sorts the asset table by `by` when clicked.
+#[component]
+fn SortableHeader(label: String, by: SortKey, sort: Signal<SortState>) -> Element {
+    let aria_sort = sort().aria_sort(by);
+    rsx! {
+        th { "aria-sort": aria_sort,
+            button {
+                r#type: "button",
+                "data-sort-key": "{by:?}",
+                onclick: move |_| sort.set(sort().toggle(by)),
+                "{label}"
+            }
+        }
+    }
+}
+
+#[component]
+fn AssetRow(asset: Asset) -> Element {
+    let repos = use_context::<Repos>();
+    let mut assets = use_context::<Signal<Vec<Asset>>>();
+    let mut confirming_delete = use_signal(|| false);
+    let id = asset.id.clone();
+    let delete = {
+        let (repos, id) = (repos.clone(), id.clone());
+        move |()| {
+            let (repos, id) = (repos.clone(), id.clone());
+            spawn(async move {
+                match repos.assets.delete(id.clone()).await {
+                    Ok(()) => {
+                        assets.write().retain(|a| a.id != id);
+                        repos.persist().await;
+                    }
+                    Err(err) => warn!("couldn't delete asset: {err}"),
+                }
+            });
+            confirming_delete.set(false);
+        }
+    };
+    let adjust = use_callback(move |delta: i64| {
+        let (repos, id) = (repos.clone(), id.clone());
+        spawn(async move {
+            match adjust_quantity(&repos, id, delta).await {
+                Ok(updated) => {
+                    if let Some(row) = assets.write().iter_mut().find(|a| a.id == updated.id) {
+                        *row = updated;
+                    }
+                }
+                Err(err) => warn!("couldn't adjust quantity: {err}"),
+            }
+        });
+    });
+    let form = use_context::<AssetFormState>();
+    let description = asset.description.as_deref().unwrap_or_default();
+    rsx! {
+        tr {
+            td {
+                a {
+                    href: "#",
+                    "data-asset-id": "{asset.id}",
+                    onclick: {
+                        let id = asset.id.clone();
+                        move |evt: MouseEvent| {
+                            evt.prevent_default();
+                            form.edit(id.clone());
+                        }
+                    },
+                    "{asset.name}"
+                }
+            }
+            td { "{description}" }
+            td { "{asset.quantity}" }
+            td {
+                button {
+                    "aria-label": "Increase quantity",
+                    onclick: move |_| adjust(1),
+                    "+"
+                }
+                button {
+                    "aria-label": "Decrease quantity",
+                    disabled: asset.quantity.get() == 0,
+                    onclick: move |_| adjust(-1),
+                    "-"
+                }
+                button { onclick: move |_| confirming_delete.set(true), "Delete" }
+                if confirming_delete() {
+                    ConfirmDialog {
+                        title: "Delete asset?",
+                        message: "\"{asset.name}\" will be removed. This can't be undone.",
+                        confirm_label: "Delete",
+                        on_confirm: delete,
+                        on_cancel: move |()| confirming_delete.set(false),
                     }
                 }
             }
         }
-        AssetForm {}
+    }
+}
+
+/// A modal asking the user to confirm something irreversible.
+///
+/// Focus starts on Cancel and Tab keeps it on the dialog's two buttons;
+/// Escape cancels.
+#[component]
+fn ConfirmDialog(
+    title: String,
+    message: String,
+    confirm_label: String,
+    on_confirm: EventHandler,
+    on_cancel: EventHandler,
+) -> Element {
+    // Mounted [cancel, confirm] buttons, and which of the two has focus.
+    let mut buttons = use_signal(|| [None::<Rc<MountedData>>, None]);
+    let mut focused = use_signal(|| 0);
+    let onkeydown = move |evt: KeyboardEvent| match evt.key() {
+        Key::Escape => on_cancel.call(()),
+        Key::Tab => {
+            // With two buttons, Tab and Shift+Tab both move to the other one.
+            evt.prevent_default();
+            if let Some(next) = buttons.read()[1 - focused()].clone() {
+                spawn(async move {
+                    _ = next.set_focus(true).await;
+                });
+            }
+        }
+        _ => {}
+    };
+    rsx! {
+        div {
+            class: "modal",
+            role: "alertdialog",
+            "aria-modal": "true",
+            "aria-labelledby": "confirm-dialog-title",
+            "aria-describedby": "confirm-dialog-message",
+            onkeydown,
+            h3 { id: "confirm-dialog-title", "{title}" }
+            p { id: "confirm-dialog-message", "{message}" }
+            footer {
+                button {
+                    r#type: "button",
+                    onmounted: move |evt| {
+                        let cancel = evt.data();
+                        buttons.write()[0] = Some(cancel.clone());
+                        spawn(async move {
+                            _ = cancel.set_focus(true).await;
+                        });
+                    },
+                    onfocus: move |_| focused.set(0),
+                    onclick: move |_| on_cancel.call(()),
+                    "Cancel"
+                }
+                button {
+                    r#type: "button",
+                    onmounted: move |evt| buttons.write()[1] = Some(evt.data()),
+                    onfocus: move |_| focused.set(1),
+                    onclick: move |_| on_confirm.call(()),
+                    "{confirm_label}"
+                }
+            }
+        }
     }
 }
 
@@ -169,6 +720,59 @@ fn LocationForm() -> Element {
 
 #[component]
 fn AssetForm() -> Element {
+    let repos = use_context::<Repos>();
+    let mut assets = use_context::<Signal<Vec<Asset>>>();
+    let selected = use_context::<Signal<Option<LocationId>>>();
+    let form = use_context::<AssetFormState>();
+    let mut draft = use_signal(AssetDraft::default);
+    let mut error = use_signal(|| None::<String>);
+    // Prefill from the stored asset when editing one.
+    let _prefill = use_resource({
+        let repos = repos.clone();
+        move || {
+            let repo = repos.assets.clone();
+            let editing = (form.editing)();
+            async move {
+                let Some(id) = editing else {
+                    draft.set(AssetDraft::default());
+                    return;
+                };
+                match repo.fetch(id).await {
+                    Ok(Some(asset)) => draft.set(AssetDraft::from(&asset)),
+                    Ok(None) => error.set(Some("This asset no longer exists.".into())),
+                    Err(err) => error.set(Some(format!("Couldn't load the asset: {err}"))),
+                }
+            }
+        }
+    });
+    let onsubmit = move |evt: FormEvent| {
+        evt.prevent_default();
+        let repos = repos.clone();
+        let editing = (form.editing)();
+        spawn(async move {
+            match save_asset(&repos, &draft.read(), editing.clone()).await {
+                Ok(asset) if editing.is_some() => {
+                    if let Some(row) = assets.write().iter_mut().find(|a| a.id == asset.id) {
+                        *row = asset;
+                    }
+                    form.close();
+                }
+                Ok(asset) => {
+                    // Only show it if it belongs in the current filter.
+                    if asset.location_id == selected() {
+                        assets.write().push(asset);
+                    }
+                    form.close();
+                }
+                Err(err) => error.set(Some(err)),
+            }
+        });
+    };
+    let title = if (form.editing)().is_some() {
+        "Edit Asset"
+    } else {
+        "Add Asset"
+    };
     rsx![
         div {
             id: "modal-asset-form",
@@ -176,10 +780,12 @@ fn AssetForm() -> Element {
             role: "dialog",
             "aria-modal": "true",
             "aria-labelledby": "asset-form-title",
-            hidden: false,
-            form { id: "asset-form",
+            form { id: "asset-form", onsubmit,
                 header {
-                    h3 { id: "asset-form-title", "Add/Edit Asset" }
+                    h3 { id: "asset-form-title", "{title}" }
+                }
+                if let Some(err) = error() {
+                    p { id: "asset-form-error", role: "alert", "{err}" }
                 }
                 label { r#for: "asset-name", "Name" }
                 input {
@@ -187,9 +793,16 @@ fn AssetForm() -> Element {
                     id: "asset-name",
                     name: "name",
                     required: true,
+                    value: "{draft.read().name}",
+                    oninput: move |evt| draft.write().name = evt.value(),
                 }
                 label { r#for: "asset-description", "Description" }
-                textarea { id: "asset-description", name: "description" }
+                textarea {
+                    id: "asset-description",
+                    name: "description",
+                    value: "{draft.read().description}",
+                    oninput: move |evt| draft.write().description = evt.value(),
+                }
                 label { r#for: "asset-quantity", "Quantity" }
                 input {
                     r#type: "number",
@@ -197,12 +810,488 @@ fn AssetForm() -> Element {
                     name: "quantity",
                     min: "0",
                     required: true,
+                    value: "{draft.read().quantity}",
+                    oninput: move |evt| draft.write().quantity = evt.value(),
                 }
                 footer {
                     button { r#type: "submit", "Save" }
-                    button { r#type: "button", id: "btn-cancel-asset", "Cancel" }
+                    button {
+                        r#type: "button",
+                        id: "btn-cancel-asset",
+                        onclick: move |_| form.close(),
+                        "Cancel"
+                    }
                 }
             }
         }
     ]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dioxus::core::{AttributeValue, ElementId, Event, Mutation};
+    use dioxus::history::{History, MemoryHistory};
+    use dioxus::html::{
+        set_event_converter, Code, Location as KeyLocation, Modifiers, PlatformEventData,
+        SerializedFormData, SerializedHtmlEventConverter, SerializedKeyboardData,
+        SerializedMouseData,
+    };
+    use std::{any::Any, time::Duration};
+
+    /// Run spawned tasks and re-render until the dom has nothing left to do,
+    /// returning the edits made along the way.
+    async fn settle(dom: &mut VirtualDom) -> Vec<Mutation> {
+        let mut edits = Vec::new();
+        while tokio::time::timeout(Duration::from_millis(50), dom.wait_for_work())
+            .await
+            .is_ok()
+        {
+            edits.extend(dom.render_immediate_to_vec().edits);
+        }
+        edits
+    }
+
+    /// The element that `edits` gave attribute `name` the text `value`.
+    fn element_with(edits: &[Mutation], name: &str, value: &str) -> ElementId {
+        edits
+            .iter()
+            .find_map(|edit| match edit {
+                Mutation::SetAttribute {
+                    name: n,
+                    value: AttributeValue::Text(v),
+                    id,
+                    ..
+                } if *n == name && v == value => Some(*id),
+                _ => None,
+            })
+            .unwrap_or_else(|| panic!("no element with {name}={value:?}"))
+    }
+
+    /// Render `root` with `repos` in context, once every pending resource
+    /// has resolved.
+    async fn render(root: fn() -> Element, repos: Repos) -> String {
+        let mut dom = VirtualDom::new(root).with_root_context(repos);
+        dom.rebuild_in_place();
+        settle(&mut dom).await;
+        dioxus_ssr::render(&dom)
+    }
+
+    /// Elements that `edits` gave an `event` handler, in document order.
+    fn listeners(edits: &[Mutation], event: &str) -> Vec<ElementId> {
+        edits
+            .iter()
+            .filter_map(|edit| match edit {
+                Mutation::NewEventListener { name, id } if name == event => Some(*id),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn fire(dom: &VirtualDom, name: &str, data: impl Any, id: ElementId) {
+        set_event_converter(Box::new(SerializedHtmlEventConverter));
+        let data = PlatformEventData::new(Box::new(data));
+        let event = Event::new(Rc::new(data) as Rc<dyn Any>, true);
+        dom.runtime().handle_event(name, event, id);
+    }
+
+    fn click(dom: &VirtualDom, id: ElementId) {
+        fire(dom, "click", SerializedMouseData::default(), id);
+    }
+
+    fn type_into(dom: &VirtualDom, text: &str, id: ElementId) {
+        fire(
+            dom,
+            "input",
+            SerializedFormData::new(text.into(), vec![]),
+            id,
+        );
+    }
+
+    fn submit(dom: &VirtualDom, id: ElementId) {
+        fire(
+            dom,
+            "submit",
+            SerializedFormData::new(String::new(), vec![]),
+            id,
+        );
+    }
+
+    fn press(dom: &VirtualDom, key: Key, id: ElementId) {
+        let data = SerializedKeyboardData::new(
+            key,
+            Code::Unidentified,
+            KeyLocation::Standard,
+            false,
+            Modifiers::empty(),
+            false,
+        );
+        fire(dom, "keydown", data, id);
+    }
+
+    fn assets_panel() -> Element {
+        use_app_state();
+        rsx! { Assets {} }
+    }
+
+    fn body() -> Element {
+        use_app_state();
+        rsx! { AssetsPage {} }
+    }
+
+    #[tokio::test]
+    async fn seeded_asset_renders_in_the_table() {
+        let repos = Repos::default();
+        let hammer = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Hammer".parse().unwrap(),
+            Some("Claw, 16oz"),
+            3u32,
+        );
+        repos.assets.create(hammer).await.unwrap();
+
+        let html = render(assets_panel, repos).await;
+        assert!(html.contains("Hammer"), "{html}");
+        assert!(html.contains("Claw, 16oz"), "{html}");
+        assert!(html.contains("<td>3</td>"), "{html}");
+        assert!(!html.contains("No assets yet."), "{html}");
+    }
+
+    #[tokio::test]
+    async fn empty_repository_shows_the_empty_state() {
+        let html = render(assets_panel, Repos::default()).await;
+        assert!(html.contains("No assets yet."), "{html}");
+    }
+
+    fn draft(name: &str, description: &str, quantity: &str) -> AssetDraft {
+        AssetDraft {
+            name: name.into(),
+            description: description.into(),
+            quantity: quantity.into(),
+            ..AssetDraft::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn submitting_a_valid_draft_adds_an_asset() {
+        let repos = Repos::default();
+        let saved = save_asset(&repos, &draft(" Tape ", "", "12"), None)
+            .await
+            .unwrap();
+        assert_eq!(saved.name, "Tape");
+        assert_eq!(saved.description, None);
+        assert_eq!(saved.quantity.get(), 12);
+        assert_eq!(repos.assets.list().await.unwrap(), vec![saved]);
+    }
+
+    #[tokio::test]
+    async fn invalid_drafts_are_rejected_without_saving() {
+        let repos = Repos::default();
+        for bad in [
+            draft("  ", "", "1"),
+            draft("Tape", "", "-1"),
+            draft("Tape", "", ""),
+        ] {
+            assert!(save_asset(&repos, &bad, None).await.is_err(), "{bad:?}");
+        }
+        assert!(repos.assets.list().await.unwrap().is_empty());
+    }
+
+    fn rows(seed: Vec<Asset>) -> Element {
+        use_app_state();
+        let mut assets = use_context::<Signal<Vec<Asset>>>();
+        use_hook(|| assets.set(seed.clone()));
+        rsx! {
+            for asset in assets.iter() {
+                AssetRow { key: "{asset.id}", asset: asset.clone() }
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn plus_increments_and_minus_is_disabled_at_zero() {
+        let repos = Repos::default();
+        let tape = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Tape".parse().unwrap(),
+            None::<String>,
+            0u32,
+        );
+        repos.assets.create(tape.clone()).await.unwrap();
+
+        let mut dom =
+            VirtualDom::new_with_props(rows, vec![tape.clone()]).with_root_context(repos.clone());
+        let buttons = listeners(&dom.rebuild_to_vec().edits, "click");
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("<td>0</td>"), "{html}");
+        assert!(html.contains("disabled=true"), "{html}");
+
+        let [_name, plus, _minus, _delete] = buttons[..] else {
+            panic!("expected name, +, - and delete buttons, got {buttons:?}");
+        };
+        click(&dom, plus);
+        settle(&mut dom).await;
+
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("<td>1</td>"), "{html}");
+        assert!(!html.contains("disabled"), "{html}");
+        let stored = repos.assets.fetch(tape.id).await.unwrap().unwrap();
+        assert_eq!(stored.quantity.get(), 1);
+    }
+
+    #[tokio::test]
+    async fn selecting_a_location_filters_the_table() {
+        let repos = Repos::default();
+        let (shed, attic) = (
+            Location::new(LocationId::new(), "Shed".parse().unwrap(), None::<String>),
+            Location::new(LocationId::new(), "Attic".parse().unwrap(), None::<String>),
+        );
+        for (location, asset) in [(&shed, "Mower"), (&attic, "Lamp")] {
+            repos.locations.create(location.clone()).await.unwrap();
+            let mut asset = Asset::new(
+                AssetId(Uuid::now_v7()),
+                asset.parse().unwrap(),
+                None::<String>,
+                1u32,
+            );
+            asset.location_id = Some(location.id.clone());
+            repos.assets.create(asset).await.unwrap();
+        }
+
+        let mut dom = VirtualDom::new(body).with_root_context(repos);
+        dom.rebuild_in_place();
+        let edits = settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("Mower") && html.contains("Lamp"), "{html}");
+        assert!(html.contains(">All Locations</span>"), "{html}");
+
+        let shed_link = element_with(&edits, "data-location-id", &shed.id.to_string());
+        click(&dom, shed_link);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("Mower") && !html.contains("Lamp"), "{html}");
+        assert!(html.contains(">Shed</span>"), "{html}");
+
+        // Clicking it again goes back to every location.
+        click(&dom, shed_link);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("Mower") && html.contains("Lamp"), "{html}");
+        assert!(html.contains(">All Locations</span>"), "{html}");
+    }
+
+    #[tokio::test]
+    async fn settings_route_renders_the_settings_page() {
+        let history: Rc<dyn History> = Rc::new(MemoryHistory::with_initial_path("/settings"));
+        let mut dom = VirtualDom::new(App).with_root_context(history);
+        dom.rebuild_in_place();
+        settle(&mut dom).await;
+
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("<h2>Settings</h2>"), "{html}");
+        assert!(!html.contains("asset-table"), "{html}");
+        assert!(
+            html.contains(r#"href="/settings" class="active""#),
+            "{html}"
+        );
+        assert_eq!(html.matches(r#"class="active""#).count(), 1, "{html}");
+    }
+
+    /// A row for a stored asset with its delete confirmation open, plus the
+    /// dialog and its [cancel, confirm] buttons.
+    async fn confirming_delete(repos: &Repos) -> (VirtualDom, ElementId, [ElementId; 2]) {
+        let tape = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Tape".parse().unwrap(),
+            None::<String>,
+            1u32,
+        );
+        repos.assets.create(tape.clone()).await.unwrap();
+        let mut dom = VirtualDom::new_with_props(rows, vec![tape]).with_root_context(repos.clone());
+        let [_name, _plus, _minus, delete] = listeners(&dom.rebuild_to_vec().edits, "click")[..]
+        else {
+            panic!("expected name, +, - and delete buttons");
+        };
+
+        click(&dom, delete);
+        let edits = settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains(r#"role="alertdialog""#), "{html}");
+        let [dialog] = listeners(&edits, "keydown")[..] else {
+            panic!("expected the dialog to handle keys");
+        };
+        let [cancel, confirm] = listeners(&edits, "click")[..] else {
+            panic!("expected cancel and confirm buttons");
+        };
+        (dom, dialog, [cancel, confirm])
+    }
+
+    #[tokio::test]
+    async fn confirming_delete_removes_the_asset() {
+        let repos = Repos::default();
+        let (mut dom, _, [_cancel, confirm]) = confirming_delete(&repos).await;
+
+        click(&dom, confirm);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(!html.contains("Tape"), "{html}");
+        assert!(repos.assets.list().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancelling_delete_keeps_the_asset() {
+        let repos = Repos::default();
+        let (mut dom, _, [cancel, _confirm]) = confirming_delete(&repos).await;
+        click(&dom, cancel);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(
+            html.contains("Tape") && !html.contains("alertdialog"),
+            "{html}"
+        );
+
+        let (mut dom, dialog, _) = confirming_delete(&repos).await;
+        press(&dom, Key::Escape, dialog);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(
+            html.contains("Tape") && !html.contains("alertdialog"),
+            "{html}"
+        );
+        assert_eq!(repos.assets.count().await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn clicking_a_name_edits_that_asset() {
+        let repos = Repos::default();
+        let mut hammer = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Hammer".parse().unwrap(),
+            Some("Claw"),
+            2u32,
+        );
+        hammer.location_id = Some(LocationId::new());
+        repos.assets.create(hammer.clone()).await.unwrap();
+
+        let mut dom = VirtualDom::new(assets_panel).with_root_context(repos.clone());
+        dom.rebuild_in_place();
+        let edits = settle(&mut dom).await;
+        click(
+            &dom,
+            element_with(&edits, "data-asset-id", &hammer.id.to_string()),
+        );
+        let edits = settle(&mut dom).await;
+
+        let html = dioxus_ssr::render(&dom);
+        assert!(html.contains("Edit Asset"), "{html}");
+        assert!(html.contains(r#"value="Hammer""#), "{html}");
+        assert!(html.contains(r#"value="Claw""#), "{html}");
+        assert!(html.contains(r#"value="2""#), "{html}");
+
+        let [name, _description, _quantity] = listeners(&edits, "input")[..] else {
+            panic!("expected three form inputs");
+        };
+        type_into(&dom, "Claw hammer", name);
+        submit(&dom, listeners(&edits, "submit")[0]);
+        settle(&mut dom).await;
+
+        let stored = repos.assets.list().await.unwrap();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].name, "Claw hammer");
+        assert_eq!(stored[0].location_id, hammer.location_id);
+        let html = dioxus_ssr::render(&dom);
+        assert!(
+            html.contains("Claw hammer") && !html.contains("Edit Asset"),
+            "{html}"
+        );
+    }
+
+    #[tokio::test]
+    async fn adding_after_editing_starts_from_a_blank_form() {
+        let repos = Repos::default();
+        let hammer = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Hammer".parse().unwrap(),
+            None::<String>,
+            2u32,
+        );
+        repos.assets.create(hammer.clone()).await.unwrap();
+
+        let mut dom = VirtualDom::new(assets_panel).with_root_context(repos);
+        let mut edits = dom.rebuild_to_vec().edits;
+        edits.extend(settle(&mut dom).await);
+        let add = listeners(&edits, "click")[0];
+        click(
+            &dom,
+            element_with(&edits, "data-asset-id", &hammer.id.to_string()),
+        );
+        settle(&mut dom).await;
+        assert!(dioxus_ssr::render(&dom).contains(r#"value="Hammer""#));
+
+        click(&dom, add);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert!(
+            html.contains("Add Asset") && !html.contains(r#"value="Hammer""#),
+            "{html}"
+        );
+    }
+
+    fn asset(name: &str, quantity: u32) -> Asset {
+        Asset::new(
+            AssetId(Uuid::now_v7()),
+            name.parse().unwrap(),
+            None::<String>,
+            quantity,
+        )
+    }
+
+    fn names(assets: &[Asset]) -> Vec<&str> {
+        assets.iter().map(|a| a.name.as_str()).collect()
+    }
+
+    #[test]
+    fn name_sort_is_case_insensitive_and_stable() {
+        let mut assets = vec![asset("bolt", 1), asset("Anvil", 2), asset("Bolt", 3)];
+        let by_name = SortState::default().toggle(SortKey::Name);
+        by_name.sort(&mut assets);
+        assert_eq!(names(&assets), ["Anvil", "bolt", "Bolt"]);
+
+        by_name.toggle(SortKey::Name).sort(&mut assets);
+        assert_eq!(names(&assets), ["bolt", "Bolt", "Anvil"]);
+    }
+
+    #[tokio::test]
+    async fn quantity_header_sorts_ascending_then_descending() {
+        let repos = Repos::default();
+        for (name, quantity) in [("Nails", 50), ("Saw", 1), ("Clamps", 4)] {
+            repos.assets.create(asset(name, quantity)).await.unwrap();
+        }
+        let mut dom = VirtualDom::new(assets_panel).with_root_context(repos);
+        let mut edits = dom.rebuild_to_vec().edits;
+        edits.extend(settle(&mut dom).await);
+        let header = element_with(&edits, "data-sort-key", "Quantity");
+
+        /// Asset names in the order the table shows them.
+        fn rows(html: &str) -> Vec<&str> {
+            let mut found: Vec<_> = ["Nails", "Saw", "Clamps"]
+                .into_iter()
+                .map(|name| (html.find(&format!(">{name}</a>")).unwrap(), name))
+                .collect();
+            found.sort();
+            found.into_iter().map(|(_, name)| name).collect()
+        }
+
+        click(&dom, header);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert_eq!(rows(&html), ["Saw", "Clamps", "Nails"]);
+        assert!(html.contains(r#"aria-sort="ascending""#), "{html}");
+
+        click(&dom, header);
+        settle(&mut dom).await;
+        let html = dioxus_ssr::render(&dom);
+        assert_eq!(rows(&html), ["Nails", "Clamps", "Saw"]);
+        assert!(html.contains(r#"aria-sort="descending""#), "{html}");
+    }
+}
@@ -0,0 +1,180 @@
+//! Keeping the repositories' contents between runs, so reloading the web app
+//! doesn't lose every asset and location.
+
+use dioxus::logger::tracing::warn;
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use stowr_core::{error::RepositoryError, Asset, Location, Repository};
+
+use crate::Repos;
+
+/// Somewhere to keep one JSON document between runs.
+pub trait Storage {
+    /// The saved document, if one was saved.
+    fn load(&self) -> Option<String>;
+
+    fn save(&self, json: &str);
+}
+
+/// Saves into a field; used off the web, where the app keeps nothing between
+/// runs, and in tests.
+#[derive(Default)]
+pub struct MemoryStorage(RefCell<Option<String>>);
+
+impl Storage for MemoryStorage {
+    fn load(&self) -> Option<String> {
+        self.0.borrow().clone()
+    }
+
+    fn save(&self, json: &str) {
+        *self.0.borrow_mut() = Some(json.to_owned());
+    }
+}
+
+/// Saves under the `stowr` key of the browser's localStorage.
+#[cfg(target_arch = "wasm32")]
+pub struct LocalStorage;
+
+#[cfg(target_arch = "wasm32")]
+impl LocalStorage {
+    const KEY: &'static str = "stowr";
+}
+
+#[cfg(target_arch = "wasm32")]
+impl Storage for LocalStorage {
+    fn load(&self) -> Option<String> {
+        use gloo_storage::Storage as _;
+        gloo_storage::LocalStorage::raw()
+            .get_item(Self::KEY)
+            .ok()
+            .flatten()
+    }
+
+    fn save(&self, json: &str) {
+        use gloo_storage::Storage as _;
+        if let Err(err) = gloo_storage::LocalStorage::raw().set_item(Self::KEY, json) {
+            warn!("couldn't write to localStorage: {err:?}");
+        }
+    }
+}
+
+/// Everything [Repos] holds, as stored.
+#[derive(Default, Serialize, Deserialize)]
+struct Saved {
+    assets: Vec<Asset>,
+    locations: Vec<Location>,
+}
+
+impl Repos {
+    /// Fill the (empty) repositories from storage. Nothing saved leaves them
+    /// empty, and so does a corrupt document, with a warning.
+    pub async fn restore(&self) -> Result<(), RepositoryError> {
+        let Some(json) = self.storage.load() else {
+            return Ok(());
+        };
+        let saved = serde_json::from_str(&json).unwrap_or_else(|err| {
+            warn!("ignoring corrupt saved data: {err}");
+            Saved::default()
+        });
+        self.assets.create_many(saved.assets).await?;
+        self.locations.create_many(saved.locations).await?;
+        Ok(())
+    }
+
+    /// Write every asset and location to storage; called after each change.
+    pub async fn persist(&self) {
+        let saved = async {
+            Ok::<_, RepositoryError>(Saved {
+                assets: self.assets.list().await?,
+                locations: self.locations.list().await?,
+            })
+        };
+        match saved.await.map(|saved| serde_json::to_string(&saved)) {
+            Ok(Ok(json)) => self.storage.save(&json),
+            Ok(Err(err)) => warn!("couldn't serialize saved data: {err}"),
+            Err(err) => warn!("couldn't read data to save: {err}"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+    use stowr_core::{asset::AssetId, location::LocationId};
+    use uuid::Uuid;
+
+    async fn seeded(storage: Rc<MemoryStorage>) -> Repos {
+        let repos = Repos::with_storage(storage);
+        let drill = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Drill".parse().unwrap(),
+            Some("Cordless"),
+            1u32,
+        );
+        repos.assets.create(drill).await.unwrap();
+        let shed = Location::new(LocationId::new(), "Shed".parse().unwrap(), None::<String>);
+        repos.locations.create(shed).await.unwrap();
+        repos
+    }
+
+    #[tokio::test]
+    async fn persisted_data_is_restored_on_reload() {
+        let storage = Rc::new(MemoryStorage::default());
+        let before = seeded(storage.clone()).await;
+        before.persist().await;
+
+        let after = Repos::with_storage(storage);
+        after.restore().await.unwrap();
+        assert_eq!(
+            after.assets.list().await.unwrap(),
+            before.assets.list().await.unwrap()
+        );
+        assert_eq!(
+            after.locations.list().await.unwrap(),
+            before.locations.list().await.unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn corrupt_data_restores_nothing() {
+        let storage = Rc::new(MemoryStorage::default());
+        storage.save(r#"{"assets": [{"id": 3}"#);
+
+        let repos = Repos::with_storage(storage);
+        repos.restore().await.unwrap();
+        assert!(repos.assets.list().await.unwrap().is_empty());
+        assert!(repos.locations.list().await.unwrap().is_empty());
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod web_tests {
+    use super::*;
+    use std::rc::Rc;
+    use stowr_core::asset::AssetId;
+    use uuid::Uuid;
+    use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+    wasm_bindgen_test_configure!(run_in_browser);
+
+    #[wasm_bindgen_test]
+    async fn local_storage_survives_a_reload() {
+        let before = Repos::with_storage(Rc::new(LocalStorage));
+        let drill = Asset::new(
+            AssetId(Uuid::now_v7()),
+            "Drill".parse().unwrap(),
+            None::<String>,
+            2u32,
+        );
+        before.assets.create(drill).await.unwrap();
+        before.persist().await;
+
+        let after = Repos::with_storage(Rc::new(LocalStorage));
+        after.restore().await.unwrap();
+        assert_eq!(
+            after.assets.list().await.unwrap(),
+            before.assets.list().await.unwrap()
+        );
+    }
+}
@@ -1,60 +1,298 @@
 use heck::ToUpperCamelCase;
 use proc_macro::TokenStream;
-use quote::{format_ident, quote};
-use syn::{parse_macro_input, Fields, FnArg, ImplItem, ItemImpl, ItemStruct, Pat, PatType, Type};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Fields, FnArg, GenericParam, ImplItem,
+    ItemImpl, ItemStruct, Pat, PatType, Type,
+};
+
+/// Options accepted by `#[domain(...)]`.
+#[derive(Default)]
+struct DomainArgs {
+    /// Extra derives for the generated struct, from `derive(Eq, Hash, ...)`
+    derives: Vec<syn::Path>,
+    /// Make fields private and generate read accessors, from `getters`
+    getters: bool,
+    /// Generate a `Default` impl with a nil id, from `default`
+    default: bool,
+    /// An existing id type to use instead of generating a tag and alias,
+    /// from `id = SomeId`
+    id: Option<syn::TypePath>,
+}
+
+impl DomainArgs {
+    fn parse(&mut self, meta: syn::meta::ParseNestedMeta) -> syn::Result<()> {
+        if meta.path.is_ident("derive") {
+            meta.parse_nested_meta(|derive| {
+                self.derives.push(derive.path);
+                Ok(())
+            })
+        } else if meta.path.is_ident("getters") {
+            self.getters = true;
+            Ok(())
+        } else if meta.path.is_ident("default") {
+            self.default = true;
+            Ok(())
+        } else if meta.path.is_ident("id") {
+            // Only a plain path can name the id in `new_from_uuid` and the
+            // registry; references, tuples and `<T as Trait>::Id` can't.
+            match meta.value()?.parse::<Type>()? {
+                Type::Path(path) if path.qself.is_none() => {
+                    self.id = Some(path);
+                    Ok(())
+                }
+                other => Err(syn::Error::new_spanned(
+                    other,
+                    "expected a path to an id type, e.g. `id = SharedId`",
+                )),
+            }
+        } else {
+            Err(meta.error(
+                "unsupported #[domain] argument, expected `derive(...)`, `getters`, \
+                 `default` or `id = ...`",
+            ))
+        }
+    }
+}
 
 /// Attribute macro to generate domain boilerplate:
 /// - Zero-variant tag enum + `RepositoryId` alias
-/// - Struct with `id` field prepended
-/// - `new(...)` constructor using `Into` for each field
+/// - Struct with `id` field prepended, keeping the struct's and fields' attributes
+/// - `new(...)` constructor using `Into` for the id and each field
+/// - `new_from_uuid(...)` constructor taking a bare `Uuid` for the id
+/// - `stowr_core::DomainInfo` registration so the domain shows up in `registered_domains()`
+///
+/// `#[domain(derive(Eq, Hash))]` appends extra derives to the generated struct.
+/// `#[domain(getters)]` makes the fields private and generates `fn <field>(&self) -> &<Type>`
+/// accessors, plus a by-value `fn id(&self)`.
+/// `#[domain(default)]` generates a `Default` impl, as `replay` and snapshots
+/// need, with every field defaulted and a nil id (`RepositoryId::nil()`). The
+/// nil id is a placeholder: replace it before the entity is persisted.
+/// `#[domain(id = SharedId)]` uses an existing id type, e.g. one shared by
+/// several domains, instead of generating the tag enum and `<Name>Id` alias.
+/// The type must convert `From<Uuid>` and be `Clone`, `Debug`, `PartialEq`,
+/// `Serialize` and `Deserialize` like the struct; with `default` it also
+/// needs an inherent `nil()` constructor.
+///
+/// Only structs with named fields are supported; tuple and unit structs are
+/// rejected with a compile error.
 #[proc_macro_attribute]
-pub fn domain(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn domain(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut args = DomainArgs::default();
+    let args_parser = syn::meta::parser(|meta| args.parse(meta));
+    parse_macro_input!(attr with args_parser);
     let input = parse_macro_input!(item as ItemStruct);
     let name = &input.ident;
     let vis = &input.vis;
 
-    let tag = format_ident!("{}Tag", name);
-    let id = format_ident!("{}Id", name);
+    // The tag and alias are only generated when no id type is given
+    let (id, id_decl, id_from_uuid, nil_id) = match &args.id {
+        Some(path) => {
+            // `new_from_uuid` converts through a check of everything the
+            // generated code needs of the type, and the `Default` impl is the
+            // only place `nil()` is called, so a bad choice is reported once,
+            // at the attribute, rather than deep inside the generated code.
+            let from_uuid = quote_spanned! {path.span()=>
+                {
+                    fn usable_as_domain_id<T>(uuid: ::uuid::Uuid) -> T
+                    where
+                        T: Clone
+                            + ::std::fmt::Debug
+                            + PartialEq
+                            + ::serde::Serialize
+                            + ::serde::de::DeserializeOwned
+                            + From<::uuid::Uuid>,
+                    {
+                        T::from(uuid)
+                    }
+                    usable_as_domain_id::<#path>(uuid)
+                }
+            };
+            let nil = quote_spanned! {path.span()=> <#path>::nil() };
+            (quote!(#path), quote!(), from_uuid, nil)
+        }
+        None => {
+            let tag = format_ident!("{}Tag", name);
+            let id = format_ident!("{}Id", name);
+            let decl = quote! {
+                #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+                #vis enum #tag {}
+                #vis type #id = RepositoryId<#tag>;
+            };
+            (
+                quote!(#id),
+                decl,
+                quote!(<#id as From<::uuid::Uuid>>::from(uuid)),
+                quote!(<#id>::nil()),
+            )
+        }
+    };
     let repo_trait = format_ident!("{}Repository", name);
     let repo_alias = format_ident!("{}Repo", name);
 
+    // `new()` and the registry need a name for every field, so positional
+    // and unit structs are rejected rather than given made-up field names.
     let fields = match &input.fields {
         Fields::Named(named) => &named.named,
-        _ => panic!("#[domain] only supports structs with named fields"),
+        Fields::Unnamed(unnamed) => {
+            return syn::Error::new_spanned(
+                unnamed,
+                format!(
+                    "#[domain] only supports structs with named fields; \
+                     name the tuple fields, e.g. `struct {name} {{ value: ... }}`"
+                ),
+            )
+            .to_compile_error()
+            .into()
+        }
+        Fields::Unit => {
+            return syn::Error::new_spanned(
+                &input,
+                format!(
+                    "#[domain] only supports structs with named fields; \
+                     `{name}` is a unit struct, declare it as `struct {name} {{ ... }}`"
+                ),
+            )
+            .to_compile_error()
+            .into()
+        }
     };
     let names: Vec<_> = fields.iter().map(|f| f.ident.as_ref().unwrap()).collect();
     let tys: Vec<_> = fields.iter().map(|f| &f.ty).collect();
+    // Doc comments, `#[serde(...)]` and any other attributes are carried over
+    let attrs = &input.attrs;
+    let field_attrs: Vec<_> = fields.iter().map(|f| &f.attrs).collect();
+    let derives = &args.derives;
+    let name_str = name.to_string();
+    let id_str = id.to_string();
+    let field_strs: Vec<_> = names.iter().map(|n| n.to_string()).collect();
+    let ty_strs: Vec<_> = tys.iter().map(|t| quote!(#t).to_string()).collect();
+
+    // Generics are threaded through the struct, its impl and the repository
+    // helpers; the id stays shared across instantiations (`RepositoryId<#tag>`).
+    let generics = &input.generics;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let mut blanket_generics = generics.clone();
+    blanket_generics.params.push(parse_quote!(__R));
+    blanket_generics
+        .make_where_clause()
+        .predicates
+        .push(parse_quote!(__R: Repository<Entity = #name #ty_generics, Id = #id> + Send + Sync));
+    let (blanket_impl_generics, _, blanket_where_clause) = blanket_generics.split_for_impl();
+    // Type aliases don't enforce bounds, so declare the alias params bare.
+    let mut alias_generics = generics.clone();
+    alias_generics.where_clause = None;
+    for param in alias_generics.params.iter_mut() {
+        match param {
+            GenericParam::Type(t) => {
+                t.bounds.clear();
+                t.colon_token = None;
+                t.eq_token = None;
+                t.default = None;
+            }
+            GenericParam::Lifetime(l) => {
+                l.bounds.clear();
+                l.colon_token = None;
+            }
+            GenericParam::Const(c) => {
+                c.eq_token = None;
+                c.default = None;
+            }
+        }
+    }
+
+    let field_vis = if args.getters { quote!() } else { quote!(pub) };
+    let getters = args.getters.then(|| {
+        quote! {
+            impl #impl_generics #name #ty_generics #where_clause {
+                /// The entity's id
+                pub fn id(&self) -> #id {
+                    self.id.clone()
+                }
+
+                #(
+                    /// Read access to the field of the same name
+                    pub fn #names(&self) -> &#tys {
+                        &self.#names
+                    }
+                )*
+            }
+        }
+    });
+
+    let default = args.default.then(|| {
+        // Each field type must be `Default`; spelling that out keeps generic
+        // domains working and points errors at the offending field type.
+        let mut default_generics = generics.clone();
+        let predicates = &mut default_generics.make_where_clause().predicates;
+        for ty in &tys {
+            predicates.push(parse_quote!(#ty: Default));
+        }
+        let (_, _, default_where_clause) = default_generics.split_for_impl();
+        quote! {
+            /// An instance with a nil id, which must be replaced before the
+            /// entity is persisted, and every field defaulted
+            impl #impl_generics Default for #name #ty_generics #default_where_clause {
+                fn default() -> Self {
+                    Self::new(#nil_id, #(<#tys as Default>::default()),*)
+                }
+            }
+        }
+    });
 
     let expanded = quote! {
         // ANCHOR: #name_domain
-        #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
-        #vis enum #tag {}
-        #vis type #id = RepositoryId<#tag>;
+        #id_decl
 
-        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
-        #vis struct #name {
-            pub id: #id,
-            #(pub #names: #tys),*
+        // The derives come first so that helpers among the struct's own
+        // attributes, e.g. `#[serde(...)]`, follow the derive introducing them.
+        #[derive(Clone, Debug, PartialEq, Serialize, Deserialize #(, #derives)*)]
+        #(#attrs)*
+        #vis struct #name #generics #where_clause {
+            #field_vis id: #id,
+            #(#(#field_attrs)* #field_vis #names: #tys),*
         }
 
-        impl #name {
+        impl #impl_generics #name #ty_generics #where_clause {
             /// Create a new instance with converted fields
             pub fn new(
-                id: #id,
+                id: impl Into<#id>,
                 #(#names: impl Into<#tys>),*
             ) -> Self {
-                #name {
-                    id,
+                Self {
+                    id: id.into(),
                     #(#names: #names.into()),*
                 }
             }
+
+            /// Create a new instance from a bare `Uuid`, e.g. a database row
+            pub fn new_from_uuid(
+                uuid: ::uuid::Uuid,
+                #(#names: impl Into<#tys>),*
+            ) -> Self {
+                Self::new(#id_from_uuid, #(#names),*)
+            }
         }
 
+        #getters
+
+        #default
+
         // Repository helper trait for this domain
-        pub trait #repo_trait: Repository<Entity = #name, Id = #id> + Send + Sync {}
-        impl<T> #repo_trait for T where T: Repository<Entity = #name, Id = #id> + Send + Sync {}
+        pub trait #repo_trait #impl_generics: Repository<Entity = #name #ty_generics, Id = #id> + Send + Sync #where_clause {}
+        impl #blanket_impl_generics #repo_trait #ty_generics for __R #blanket_where_clause {}
         /// Arc-ed trait object alias for repositories of this domain
-        pub type #repo_alias = std::sync::Arc<dyn #repo_trait>;
+        pub type #repo_alias #alias_generics = std::sync::Arc<dyn #repo_trait #ty_generics>;
+
+        ::stowr_core::inventory::submit! {
+            ::stowr_core::DomainInfo {
+                name: #name_str,
+                fields: &[
+                    ::stowr_core::FieldInfo { name: "id", ty: #id_str },
+                    #(::stowr_core::FieldInfo { name: #field_strs, ty: #ty_strs }),*
+                ],
+            }
+        }
         // ANCHOR_END: #name_domain
     };
 
@@ -69,32 +307,96 @@ pub fn command(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
 /// Attribute macro to generate command/event enums and Aggregate implementation
 /// from an `impl` block with `#[command]` methods.
+///
+/// A `#[command]` returning `Result<(), E>` is fallible and must name a check
+/// with `#[command(validate = can_add)]`, a `fn can_add(&self, by: &u8) ->
+/// Result<(), E>` taking the command's arguments by reference. `handle_command`
+/// runs the check instead of the command and returns its error converted into
+/// the aggregate's `Error`, so the command itself only runs once, when its
+/// event is applied.
+///
+/// Each command's event variant shares its name unless renamed with
+/// `#[command(event = Name)]`, e.g. `MoveTo` emitting `MovedTo`.
+///
+/// Each command also gets a struct of its own, named after the aggregate and
+/// the command, e.g. `AssetMoveToCommand { .. }`, that converts into the
+/// command enum with `From`.
+///
+/// `#[domain_impl(timestamp)]` adds an `occurred_at: SystemTime` field to every
+/// event variant, set to `stowr_core::now()` by `handle_command`, which reads
+/// the browser's clock on wasm32 where `SystemTime::now()` panics.
 #[proc_macro_attribute]
-pub fn domain_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
+pub fn domain_impl(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let mut timestamp = false;
+    let args_parser = syn::meta::parser(|meta| {
+        if meta.path.is_ident("timestamp") {
+            timestamp = true;
+            Ok(())
+        } else {
+            Err(meta.error("unsupported #[domain_impl] argument, expected `timestamp`"))
+        }
+    });
+    parse_macro_input!(attr with args_parser);
     let input = parse_macro_input!(item as ItemImpl);
     // Extract the implementor type, e.g. `Foo`
     let self_ty = match &*input.self_ty {
         Type::Path(tp) => tp.path.segments.last().unwrap().ident.clone(),
-        _ => panic!("#[domain_impl] only supports inherent impls on a type"),
+        _ => {
+            return syn::Error::new_spanned(
+                &input.self_ty,
+                "#[domain_impl] only supports inherent impls on a type",
+            )
+            .to_compile_error()
+            .into()
+        }
     };
 
+    let cmd_enum = format_ident!("{}Command", self_ty);
+    let evt_enum = format_ident!("{}Event", self_ty);
+
     // Prepare lists for generated code
     let mut cmd_variants = Vec::new();
     let mut evt_variants = Vec::new();
     let mut handle_arms = Vec::new();
     let mut apply_arms = Vec::new();
+    let mut cmd_structs = Vec::new();
 
     // Iterate methods to find #[command]
     for item in &input.items {
         if let ImplItem::Fn(m) = item {
-            let is_cmd = m.attrs.iter().any(|a| a.path().is_ident("command"));
-            if !is_cmd {
+            let Some(cmd_attr) = m.attrs.iter().find(|a| a.path().is_ident("command")) else {
                 continue;
-            }
+            };
 
             let method = &m.sig.ident;
             let variant_name = format_ident!("{}", method.to_string().to_upper_camel_case());
 
+            // `#[command(event = Name)]` names the event variant; by default it
+            // shares the command's name. `validate = check` names the method
+            // that checks the command's arguments in `handle_command`.
+            let mut event_name = None;
+            let mut validator = None;
+            if let syn::Meta::List(_) = &cmd_attr.meta {
+                let parsed = cmd_attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("event") {
+                        event_name = Some(meta.value()?.parse::<syn::Ident>()?);
+                        Ok(())
+                    } else if meta.path.is_ident("validate") {
+                        validator = Some(meta.value()?.parse::<syn::Ident>()?);
+                        Ok(())
+                    } else {
+                        Err(meta.error(
+                            "unsupported #[command] argument, expected `event = Name` or \
+                             `validate = check`",
+                        ))
+                    }
+                });
+                if let Err(err) = parsed {
+                    return err.to_compile_error().into();
+                }
+            }
+            let evt_variant = event_name.unwrap_or_else(|| variant_name.clone());
+
             // Collect argument names and types
             let mut fields = Vec::new();
             for arg in &m.sig.inputs {
@@ -106,58 +408,126 @@ pub fn domain_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
             }
             let names: Vec<_> = fields.iter().map(|(ident, _)| ident).collect();
             let types: Vec<_> = fields.iter().map(|(_, ty)| ty).collect();
+            if let Some(clash) = names.iter().find(|n| timestamp && **n == "occurred_at") {
+                return syn::Error::new_spanned(
+                    clash,
+                    "`occurred_at` is the event timestamp added by #[domain_impl(timestamp)]",
+                )
+                .to_compile_error()
+                .into();
+            }
+
+            // With `timestamp`, events carry when they happened on top of the
+            // command's args; applying them ignores it.
+            let (evt_time_field, evt_time_now, evt_time_rest) = if timestamp {
+                (
+                    quote!(occurred_at: std::time::SystemTime),
+                    quote!(occurred_at: ::stowr_core::now()),
+                    quote!(..),
+                )
+            } else {
+                (quote!(), quote!(), quote!())
+            };
 
             // Build enum variants
             cmd_variants.push(quote! { #variant_name { #(#names: #types),* } });
-            evt_variants.push(quote! { #variant_name { #(#names: #types),* } });
+            evt_variants.push(quote! { #evt_variant { #(#names: #types,)* #evt_time_field } });
+
+            // A standalone struct per command, for building one without
+            // spelling out the enum variant, converted with `.into()`. It's
+            // prefixed with the aggregate, so a `reset` command on `Reset`
+            // doesn't clash with the `ResetCommand` enum.
+            let cmd_struct = format_ident!("{}{}Command", self_ty, variant_name);
+            let cmd_doc =
+                format!("Arguments of the `{variant_name}` command, see [{self_ty}::{method}]");
+            cmd_structs.push(quote! {
+                #[doc = #cmd_doc]
+                #[derive(Clone, Debug, Serialize, Deserialize)]
+                pub struct #cmd_struct {
+                    #(pub #names: #types),*
+                }
+
+                impl From<#cmd_struct> for #cmd_enum {
+                    fn from(cmd: #cmd_struct) -> Self {
+                        #cmd_enum::#variant_name { #(#names: cmd.#names),* }
+                    }
+                }
+            });
+
+            // `apply_event` is the only place a command mutates the aggregate.
+            // Infallible commands go straight to an event; fallible ones are
+            // checked by their validator, which only reads the aggregate.
+            let fallible = returns_result(&m.sig);
+            let try_op = fallible.then(|| quote!(?));
 
             // Determine if this is an instance method or static constructor
             let is_method = m.sig.receiver().is_some();
-            let cmd_enum = format_ident!("{}Command", self_ty);
-            let evt_enum = format_ident!("{}Event", self_ty);
+            if fallible && is_method && validator.is_none() {
+                let err = syn::Error::new_spanned(
+                    &m.sig.output,
+                    format!(
+                        "a fallible #[command] needs `#[command(validate = ...)]` naming a \
+                         `&self` check of its arguments, so `{method}` only runs when its \
+                         event is applied"
+                    ),
+                )
+                .to_compile_error();
+                // Keep the impl so the error isn't buried under unresolved
+                // calls to its methods
+                return quote!(#input #err).into();
+            }
 
             if is_method {
                 // instance method
+                let validate = validator.as_ref().map(|check| {
+                    quote! {
+                        self.#check(#(&#names),*)?;
+                    }
+                });
                 handle_arms.push(quote! {
                     #cmd_enum::#variant_name { #(#names),* } => {
-                        let mut agg = self.clone();
-                        agg.#method(#(#names.clone()),*);
-                        vec![#evt_enum::#variant_name { #(#names),* }]
+                        #validate
+                        vec![#evt_enum::#evt_variant { #(#names,)* #evt_time_now }]
                     }
                 });
 
                 apply_arms.push(quote! {
-                    #evt_enum::#variant_name { #(#names),* } => {
-                        self.#method(#(#names.clone()),*);
+                    #evt_enum::#evt_variant { #(#names,)* #evt_time_rest } => {
+                        self.#method(#(#names.clone()),*)#try_op;
+                        Ok(())
                     }
                 });
             } else {
-                // static constructor
+                // static constructor; applying its event calls `new`, so
+                // running it here to validate doesn't run it twice
+                let validate = match &validator {
+                    Some(check) => Some(quote! { #self_ty::#check(#(&#names),*)?; }),
+                    None => fallible.then(|| quote! { #self_ty::#method(#(#names.clone()),*)?; }),
+                };
                 handle_arms.push(quote! {
                     #cmd_enum::#variant_name { #(#names),* } => {
-                        #self_ty::#method(#(#names.clone()),*);
-                        vec![#evt_enum::#variant_name { #(#names),* }]
+                        #validate
+                        vec![#evt_enum::#evt_variant { #(#names,)* #evt_time_now }]
                     }
                 });
 
                 apply_arms.push(quote! {
-                    #evt_enum::#variant_name { #(#names),* } => {
+                    #evt_enum::#evt_variant { #(#names,)* #evt_time_rest } => {
                         *self = #self_ty::new(#(#names.clone()),*);
+                        Ok(())
                     }
                 });
             }
         }
     }
 
-    // Final enum names
-    let cmd_enum = format_ident!("{}Command", self_ty);
-    let evt_enum = format_ident!("{}Event", self_ty);
-
     // Assemble the expanded code
     let expanded = quote! {
         #input
 
-        #[derive(Clone, Debug)]
+        // Command fields reuse the argument type tokens, so an argument type
+        // that isn't serde-friendly is reported at the `#[command]` signature.
+        #[derive(Clone, Debug, Serialize, Deserialize)]
         pub enum #cmd_enum {
             #(#cmd_variants),*
         }
@@ -167,9 +537,13 @@ pub fn domain_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
             #(#evt_variants),*
         }
 
+        #(#cmd_structs)*
+
         impl Aggregate for #self_ty {
             type Command = #cmd_enum;
             type Event   = #evt_enum;
+            // Fallible commands' errors are converted with `?`, so they
+            // need a `From` impl into the crate's `AggregateError`.
             type Error   = crate::AggregateError;
 
             fn handle_command(&self, cmd: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
@@ -178,7 +552,7 @@ pub fn domain_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
                 })
             }
 
-            fn apply_event(&mut self, evt: &Self::Event) {
+            fn apply_event(&mut self, evt: &Self::Event) -> Result<(), Self::Error> {
                 match evt {
                     #(#apply_arms),*
                 }
@@ -188,3 +562,18 @@ pub fn domain_impl(_attr: TokenStream, item: TokenStream) -> TokenStream {
 
     TokenStream::from(expanded)
 }
+
+/// Whether a method's declared return type is a `Result<..>`.
+fn returns_result(sig: &syn::Signature) -> bool {
+    match &sig.output {
+        syn::ReturnType::Type(_, ty) => match ty.as_ref() {
+            Type::Path(tp) => tp
+                .path
+                .segments
+                .last()
+                .is_some_and(|seg| seg.ident == "Result"),
+            _ => false,
+        },
+        syn::ReturnType::Default => false,
+    }
+}
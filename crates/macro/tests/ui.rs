@@ -5,4 +5,21 @@ fn ui_tests() {
     let t = TestCases::new();
     t.pass("tests/ui/01-domain.rs");
     t.pass("tests/ui/02-domain-impl.rs");
+    t.compile_fail("tests/ui/03-domain-tuple-struct.rs");
+    t.pass("tests/ui/04-domain-generic.rs");
+    t.pass("tests/ui/05-domain-attrs.rs");
+    t.pass("tests/ui/06-domain-derive-args.rs");
+    t.compile_fail("tests/ui/07-domain-unknown-args.rs");
+    t.pass("tests/ui/08-domain-getters.rs");
+    t.pass("tests/ui/09-domain-impl-fallible.rs");
+    t.pass("tests/ui/10-domain-impl-single-apply.rs");
+    t.pass("tests/ui/11-domain-impl-command-serde.rs");
+    t.pass("tests/ui/12-domain-impl-event-name.rs");
+    t.pass("tests/ui/13-domain-default.rs");
+    t.compile_fail("tests/ui/14-domain-unit-struct.rs");
+    t.pass("tests/ui/15-domain-impl-command-structs.rs");
+    t.pass("tests/ui/16-domain-impl-timestamp.rs");
+    t.pass("tests/ui/17-domain-id.rs");
+    t.compile_fail("tests/ui/18-domain-id-unusable.rs");
+    t.compile_fail("tests/ui/19-domain-impl-fallible-needs-validate.rs");
 }
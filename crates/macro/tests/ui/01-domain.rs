@@ -1,27 +1,8 @@
-// A smoke test for #[domain]
+// A smoke test for #[domain]; the expansion needs no `use uuid::Uuid`
 extern crate stowr_macro;
-use serde::{Deserialize, Serialize};
 use stowr_macro::domain;
 
-// Stub the Repository trait so expansion resolves
-pub trait Repository {
-    type Entity;
-    type Id;
-}
-
-// Stub the RepositoryId so expansion resolves
-#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
-pub struct RepositoryId<T> {
-    _marker: std::marker::PhantomData<T>,
-}
-
-impl<T> Default for RepositoryId<T> {
-    fn default() -> Self {
-        RepositoryId {
-            _marker: std::marker::PhantomData,
-        }
-    }
-}
+include!("common/mod.rs");
 
 #[domain]
 pub struct Bar {
@@ -30,8 +11,21 @@ pub struct Bar {
 }
 
 fn main() {
-    let id: BarId = Default::default();
+    let id = BarId::new();
     let bar = Bar::new(id.clone(), "hello", 42);
     assert_eq!(bar.a, "hello");
     assert_eq!(bar.b, 42);
+
+    let uuid = uuid::Uuid::new_v4();
+    let from_uuid = Bar::new_from_uuid(uuid, "hello", 42);
+    assert_eq!(from_uuid.id.value, uuid);
+    let via_into = Bar::new(uuid, "hello", 42);
+    assert_eq!(via_into, from_uuid);
+
+    let info = inventory::iter::<DomainInfo>
+        .into_iter()
+        .find(|d| d.name == "Bar")
+        .expect("Bar is registered");
+    let fields: Vec<_> = info.fields.iter().map(|f| (f.name, f.ty)).collect();
+    assert_eq!(fields, [("id", "BarId"), ("a", "String"), ("b", "i32")]);
 }
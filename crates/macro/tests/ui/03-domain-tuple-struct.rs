@@ -0,0 +1,8 @@
+// #[domain] needs named fields to build `new()`, so tuple structs are rejected
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+#[domain]
+pub struct Sku(String);
+
+fn main() {}
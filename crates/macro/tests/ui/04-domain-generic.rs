@@ -0,0 +1,32 @@
+// #[domain] on a struct with generic parameters
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+include!("common/mod.rs");
+
+#[domain]
+pub struct Crate<T: Clone>
+where
+    T: PartialEq,
+{
+    label: String,
+    contents: Vec<T>,
+}
+
+// The repository helpers are generic too
+struct VecCrateRepo;
+
+impl Repository for VecCrateRepo {
+    type Entity = Crate<u8>;
+    type Id = CrateId;
+}
+
+fn main() {
+    let id = CrateId::new();
+    let c = Crate::new(id, "bolts", vec![1u8, 2, 3]);
+    assert_eq!(c.label, "bolts");
+    assert_eq!(c.contents, vec![1, 2, 3]);
+
+    let repo: CrateRepo<u8> = std::sync::Arc::new(VecCrateRepo);
+    let _ = repo;
+}
@@ -0,0 +1,31 @@
+// Attributes on a #[domain] struct and its fields survive expansion, after
+// the generated derives so derive helpers like `#[serde]` resolve
+#![deny(legacy_derive_helpers)]
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+include!("common/mod.rs");
+
+/// A person with a serialized `full_name`
+#[domain]
+#[derive(Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Person {
+    /// How the person is addressed
+    #[serde(rename = "full_name")]
+    name: String,
+}
+
+fn main() {
+    let person = Person::new(PersonId::new(), "Ada Lovelace");
+    let json = serde_json::to_value(&person).unwrap();
+    assert_eq!(json["full_name"], "Ada Lovelace");
+    assert!(json.get("name").is_none());
+
+    let back: Person = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(back, person);
+
+    let mut extra = json;
+    extra["nickname"] = "Ada".into();
+    assert!(serde_json::from_value::<Person>(extra).is_err());
+}
@@ -0,0 +1,22 @@
+// #[domain(derive(...))] appends to the generated derive list
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+include!("common/mod.rs");
+
+#[domain(derive(Eq, Hash))]
+pub struct Tool {
+    name: String,
+}
+
+fn assert_eq_hash<T: Eq + std::hash::Hash>() {}
+
+fn main() {
+    assert_eq_hash::<Tool>();
+
+    let id = ToolId::new();
+    let mut set = std::collections::HashSet::new();
+    set.insert(Tool::new(id.clone(), "hammer"));
+    set.insert(Tool::new(id, "hammer"));
+    assert_eq!(set.len(), 1);
+}
@@ -0,0 +1,10 @@
+// Unknown #[domain(...)] arguments are rejected instead of silently ignored
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+#[domain(frobnicate)]
+pub struct Tool {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,26 @@
+// #[domain(getters)] hides the fields behind read accessors
+extern crate stowr_macro;
+use stowr_macro::domain;
+use uuid::Uuid;
+
+include!("common/mod.rs");
+
+mod shop {
+    use super::*;
+
+    #[domain(getters)]
+    pub struct Tool {
+        name: String,
+        weight: u32,
+    }
+}
+
+use shop::{Tool, ToolId};
+
+fn main() {
+    let id: ToolId = Uuid::new_v4().into();
+    let tool = Tool::new(id.clone(), "hammer", 3u32);
+    assert_eq!(tool.id(), id);
+    assert_eq!(tool.name(), "hammer");
+    assert_eq!(*tool.weight(), 3);
+}
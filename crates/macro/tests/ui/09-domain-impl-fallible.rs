@@ -0,0 +1,44 @@
+// A #[command] returning Result rejects invalid input in handle_command,
+// through the validator it names
+extern crate stowr_macro;
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+#[domain]
+pub struct Counter {
+    val: u8,
+}
+
+#[domain_impl]
+impl Counter {
+    #[command(validate = can_add)]
+    pub fn add(&mut self, by: u8) -> Result<(), AggregateError> {
+        self.val = self.added(by)?;
+        Ok(())
+    }
+
+    fn can_add(&self, by: &u8) -> Result<(), AggregateError> {
+        self.added(*by).map(drop)
+    }
+
+    fn added(&self, by: u8) -> Result<u8, AggregateError> {
+        self.val
+            .checked_add(by)
+            .ok_or_else(|| AggregateError::Invalid(format!("cannot add {by}")))
+    }
+}
+
+fn main() {
+    let counter = Counter::new(CounterId::new(), 250u8);
+
+    let events = counter
+        .handle_command(CounterCommand::Add { by: 5 })
+        .unwrap();
+    assert!(matches!(events.as_slice(), [CounterEvent::Add { by: 5 }]));
+
+    let err = counter
+        .handle_command(CounterCommand::Add { by: 10 })
+        .unwrap_err();
+    assert_eq!(err, AggregateError::Invalid("cannot add 10".into()));
+}
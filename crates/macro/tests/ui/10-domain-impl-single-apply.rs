@@ -0,0 +1,93 @@
+// handle_command only emits events; apply_event runs the command body once,
+// fallible commands included
+extern crate stowr_macro;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+static PUSHES: AtomicUsize = AtomicUsize::new(0);
+static POPS: AtomicUsize = AtomicUsize::new(0);
+
+#[domain]
+pub struct Tally {
+    items: Vec<String>,
+}
+
+#[domain_impl]
+impl Tally {
+    #[command]
+    pub fn push(&mut self, item: String) {
+        PUSHES.fetch_add(1, Ordering::SeqCst);
+        self.items.push(item);
+    }
+
+    #[command(validate = can_pop)]
+    pub fn pop(&mut self, n: usize) -> Result<(), AggregateError> {
+        POPS.fetch_add(1, Ordering::SeqCst);
+        self.can_pop(&n)?;
+        self.items.truncate(self.items.len() - n);
+        Ok(())
+    }
+
+    fn can_pop(&self, n: &usize) -> Result<(), AggregateError> {
+        match *n <= self.items.len() {
+            true => Ok(()),
+            false => Err(AggregateError::Invalid(format!(
+                "only {} to pop",
+                self.items.len()
+            ))),
+        }
+    }
+}
+
+fn run(tally: &mut Tally, cmd: TallyCommand) -> Result<(), AggregateError> {
+    for evt in &tally.handle_command(cmd)? {
+        tally.apply_event(evt)?;
+    }
+    Ok(())
+}
+
+fn main() {
+    let mut tally = Tally::new(TallyId::new(), Vec::<String>::new());
+
+    let events = tally
+        .handle_command(TallyCommand::Push {
+            item: "bolt".into(),
+        })
+        .unwrap();
+    assert_eq!(
+        PUSHES.load(Ordering::SeqCst),
+        0,
+        "handle_command must not mutate"
+    );
+    assert!(tally.items.is_empty());
+
+    for evt in &events {
+        tally.apply_event(evt).unwrap();
+    }
+    assert_eq!(PUSHES.load(Ordering::SeqCst), 1);
+    assert_eq!(tally.items, vec!["bolt".to_string()]);
+
+    // A fallible command is checked by its validator, not run, when handled
+    run(&mut tally, TallyCommand::Push { item: "nut".into() }).unwrap();
+    let events = tally.handle_command(TallyCommand::Pop { n: 1 }).unwrap();
+    assert_eq!(
+        POPS.load(Ordering::SeqCst),
+        0,
+        "handle_command must not run pop"
+    );
+    for evt in &events {
+        tally.apply_event(evt).unwrap();
+    }
+    assert_eq!(POPS.load(Ordering::SeqCst), 1);
+    assert_eq!(tally.items, vec!["bolt".to_string()]);
+
+    let err = run(&mut tally, TallyCommand::Pop { n: 5 }).unwrap_err();
+    assert_eq!(err, AggregateError::Invalid("only 1 to pop".into()));
+    assert_eq!(
+        POPS.load(Ordering::SeqCst),
+        1,
+        "a rejected command never runs"
+    );
+}
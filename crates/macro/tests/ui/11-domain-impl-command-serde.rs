@@ -0,0 +1,32 @@
+// Generated command enums round-trip through serde
+extern crate stowr_macro;
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+#[domain]
+pub struct Baz {
+    val: u8,
+}
+
+#[domain_impl]
+impl Baz {
+    #[command]
+    pub fn create(_id: BazId, _val: u8) {
+        // initial state
+    }
+
+    #[command]
+    pub fn increment(&mut self, by: u8) {
+        self.val = self.val.wrapping_add(by);
+    }
+}
+
+fn main() {
+    let cmd = BazCommand::Increment { by: 5 };
+    let json = serde_json::to_string(&cmd).unwrap();
+    assert_eq!(json, r#"{"Increment":{"by":5}}"#);
+
+    let parsed: BazCommand = serde_json::from_str(&json).unwrap();
+    assert!(matches!(parsed, BazCommand::Increment { by: 5 }));
+}
@@ -0,0 +1,39 @@
+// #[command(event = Name)] renames the generated event variant
+extern crate stowr_macro;
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+
+#[domain]
+pub struct Baz {
+    val: u8,
+}
+
+#[domain_impl]
+impl Baz {
+    #[command(event = Incremented)]
+    pub fn increment(&mut self, by: u8) {
+        self.val = self.val.wrapping_add(by);
+    }
+
+    #[command]
+    pub fn reset(&mut self) {
+        self.val = 0;
+    }
+}
+
+fn main() {
+    let mut baz = Baz::new(BazId::new(), 1u8);
+    let events = baz
+        .handle_command(BazCommand::Increment { by: 2 })
+        .unwrap();
+    assert!(matches!(events[..], [BazEvent::Incremented { by: 2 }]));
+
+    baz.apply_event(&events[0]).unwrap();
+    assert_eq!(baz.val, 3);
+
+    // Unrenamed commands keep sharing their name with the event.
+    let events = baz.handle_command(BazCommand::Reset {}).unwrap();
+    assert!(matches!(events[..], [BazEvent::Reset {}]));
+}
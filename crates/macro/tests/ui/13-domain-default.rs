@@ -0,0 +1,36 @@
+// #[domain(default)] generates a Default impl with a nil id
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+include!("common/mod.rs");
+
+#[domain(default)]
+pub struct Tool {
+    name: String,
+    weight: u32,
+    tags: Vec<String>,
+}
+
+// Generic domains only need their field types to be `Default`, not `T`
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Widget(u32);
+
+#[domain(default)]
+pub struct Bin<T: Clone> {
+    label: String,
+    contents: Vec<T>,
+}
+
+fn main() {
+    let tool = Tool::default();
+    assert!(tool.id.is_nil());
+    assert_eq!(tool.name, "");
+    assert_eq!(tool.weight, 0);
+    assert!(tool.tags.is_empty());
+
+    // `Widget` isn't `Default`, yet `Bin<Widget>` is
+    let bin = Bin::<Widget>::default();
+    assert!(bin.id.is_nil());
+    assert_eq!(bin.label, "");
+    assert!(bin.contents.is_empty());
+}
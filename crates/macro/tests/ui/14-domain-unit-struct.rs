@@ -0,0 +1,8 @@
+// A unit struct has no fields for `new()` to take, so it is rejected too
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+#[domain]
+pub struct Marker;
+
+fn main() {}
@@ -0,0 +1,57 @@
+// Each #[command] gets a struct that converts into the command enum
+extern crate stowr_macro;
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+#[domain]
+pub struct Baz {
+    val: u8,
+}
+
+#[domain_impl]
+impl Baz {
+    #[command(event = Incremented)]
+    pub fn increment(&mut self, by: u8) {
+        self.val = self.val.wrapping_add(by);
+    }
+
+    #[command]
+    pub fn reset(&mut self) {
+        self.val = 0;
+    }
+}
+
+#[domain]
+pub struct Reset {
+    count: u8,
+}
+
+#[domain_impl]
+impl Reset {
+    #[command]
+    pub fn reset(&mut self) {
+        self.count += 1;
+    }
+}
+
+fn main() {
+    let mut baz = Baz::new(BazId::new(), 1u8);
+    let cmd: BazCommand = BazIncrementCommand { by: 2 }.into();
+    assert!(matches!(cmd, BazCommand::Increment { by: 2 }));
+
+    let events = baz.handle_command(cmd).unwrap();
+    assert!(matches!(events[..], [BazEvent::Incremented { by: 2 }]));
+    baz.apply_event(&events[0]).unwrap();
+    assert_eq!(baz.val, 3);
+
+    // Commands without arguments get an empty struct
+    let events = baz.handle_command(BazResetCommand {}.into()).unwrap();
+    assert!(matches!(events[..], [BazEvent::Reset {}]));
+
+    // A command named after its aggregate doesn't clash with the enum
+    let reset = Reset::new(ResetId::new(), 0u8);
+    let cmd: ResetCommand = ResetResetCommand {}.into();
+    assert!(matches!(cmd, ResetCommand::Reset {}));
+    assert_eq!(reset.handle_command(cmd).unwrap().len(), 1);
+}
@@ -0,0 +1,46 @@
+// #[domain_impl(timestamp)] stamps every event with when it happened
+extern crate stowr_macro;
+use std::time::{Duration, SystemTime};
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+#[domain]
+pub struct Baz {
+    val: u8,
+}
+
+#[domain_impl(timestamp)]
+impl Baz {
+    #[command(event = Incremented)]
+    pub fn increment(&mut self, by: u8) {
+        self.val = self.val.wrapping_add(by);
+    }
+
+    #[command]
+    pub fn reset(&mut self) {
+        self.val = 0;
+    }
+}
+
+fn occurred_at(event: &BazEvent) -> SystemTime {
+    match event {
+        BazEvent::Incremented { occurred_at, .. } | BazEvent::Reset { occurred_at } => *occurred_at,
+    }
+}
+
+fn main() {
+    let mut baz = Baz::new(BazId::new(), 1u8);
+    let before = SystemTime::now();
+    let first = baz.handle_command(BazCommand::Increment { by: 2 }).unwrap();
+    assert!(matches!(first[..], [BazEvent::Incremented { by: 2, .. }]));
+    assert!(occurred_at(&first[0]) >= before);
+
+    // The timestamp doesn't get in the way of applying the event
+    baz.apply_event(&first[0]).unwrap();
+    assert_eq!(baz.val, 3);
+
+    std::thread::sleep(Duration::from_millis(10));
+    let second = baz.handle_command(BazCommand::Reset {}).unwrap();
+    assert!(occurred_at(&second[0]) > occurred_at(&first[0]));
+}
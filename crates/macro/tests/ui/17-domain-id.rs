@@ -0,0 +1,43 @@
+// #[domain(id = ...)] uses an existing id type instead of generating one, so
+// several domains can share an id space
+extern crate stowr_macro;
+use stowr_macro::domain;
+use uuid::Uuid;
+
+include!("common/mod.rs");
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ItemTag {}
+pub type ItemId = RepositoryId<ItemTag>;
+
+#[domain(id = ItemId)]
+pub struct Tool {
+    name: String,
+}
+
+#[domain(id = ItemId, default)]
+pub struct Part {
+    sku: String,
+}
+
+// A full path works too
+#[domain(id = crate::RepositoryId<ItemTag>, getters)]
+pub struct Kit {
+    label: String,
+}
+
+fn same_space(tool: &Tool, part: &Part, kit: &Kit) -> bool {
+    tool.id == part.id && part.id == kit.id()
+}
+
+fn main() {
+    let uuid = Uuid::new_v4();
+    let tool = Tool::new_from_uuid(uuid, "drill");
+    let part = Part::new(ItemId::from(uuid), "DR-18V");
+    let kit = Kit::new_from_uuid(uuid, "drilling");
+    assert!(same_space(&tool, &part, &kit));
+    assert!(Part::default().id.is_nil());
+
+    // No tag or alias of their own is generated
+    let _: ItemId = tool.id;
+}
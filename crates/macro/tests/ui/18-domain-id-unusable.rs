@@ -0,0 +1,27 @@
+// The id type given to #[domain(id = ...)] must be a path to a type that
+// converts from a `Uuid`, and with `default` has a `nil()` constructor; each
+// missing piece is reported once, at the attribute
+extern crate stowr_macro;
+use stowr_macro::domain;
+
+include!("common/mod.rs");
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Sku(String);
+
+#[domain(id = Sku)]
+pub struct Tool {
+    name: String,
+}
+
+#[domain(id = &'static str)]
+pub struct Part {
+    name: String,
+}
+
+#[domain(id = Sku, default)]
+pub struct Bin {
+    label: String,
+}
+
+fn main() {}
@@ -0,0 +1,25 @@
+// A fallible #[command] must name its validator, so handle_command doesn't
+// have to run the command to find out whether it fails
+extern crate stowr_macro;
+use stowr_macro::{command, domain, domain_impl};
+
+include!("common/mod.rs");
+
+#[domain]
+pub struct Counter {
+    val: u8,
+}
+
+#[domain_impl]
+impl Counter {
+    #[command]
+    pub fn add(&mut self, by: u8) -> Result<(), AggregateError> {
+        self.val = self
+            .val
+            .checked_add(by)
+            .ok_or(AggregateError::Invalid("overflow".into()))?;
+        Ok(())
+    }
+}
+
+fn main() {}
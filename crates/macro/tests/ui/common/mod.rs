@@ -0,0 +1,77 @@
+// Stubs of the stowr-core items that #[domain] and #[domain_impl] expansions
+// refer to, so each UI test compiles on its own. Tests pull this in with
+// `include!("common/mod.rs");` rather than `mod common;`, as the expansions
+// name these items from the crate root.
+use serde::{Deserialize, Serialize};
+
+// The expansions reach the registry through `::stowr_core`
+extern crate self as stowr_core;
+pub use inventory;
+
+// Stub the Repository trait so expansion resolves
+pub trait Repository {
+    type Entity;
+    type Id;
+}
+
+// Stub the Aggregate trait and its error
+pub trait Aggregate {
+    type Command;
+    type Event;
+    type Error;
+    fn handle_command(&self, cmd: Self::Command) -> Result<Vec<Self::Event>, Self::Error>;
+    fn apply_event(&mut self, evt: &Self::Event) -> Result<(), Self::Error>;
+}
+
+#[derive(Debug, PartialEq)]
+pub enum AggregateError {
+    Invalid(String),
+}
+
+// Stub the domain registry so expansion resolves
+pub struct DomainInfo {
+    pub name: &'static str,
+    pub fields: &'static [FieldInfo],
+}
+
+pub struct FieldInfo {
+    pub name: &'static str,
+    pub ty: &'static str,
+}
+
+inventory::collect!(DomainInfo);
+
+// Stub the clock #[domain_impl(timestamp)] stamps events with
+pub fn now() -> std::time::SystemTime {
+    std::time::SystemTime::now()
+}
+
+// Stub the RepositoryId so expansion resolves
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct RepositoryId<T> {
+    value: uuid::Uuid,
+    _marker: std::marker::PhantomData<T>,
+}
+
+impl<T> RepositoryId<T> {
+    pub fn new() -> Self {
+        uuid::Uuid::new_v4().into()
+    }
+
+    pub fn nil() -> Self {
+        uuid::Uuid::nil().into()
+    }
+
+    pub fn is_nil(&self) -> bool {
+        self.value.is_nil()
+    }
+}
+
+impl<T> From<uuid::Uuid> for RepositoryId<T> {
+    fn from(value: uuid::Uuid) -> Self {
+        RepositoryId {
+            value,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
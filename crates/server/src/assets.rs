@@ -0,0 +1,166 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use stowr_core::{
+    asset::{Asset, AssetCommand, AssetEvent, AssetId},
+    error::RepositoryError,
+    location::LocationId,
+    validation::Name,
+    Aggregate,
+};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{ApiError, AppState, ErrorBody};
+
+/// The body of `POST /assets`; the server picks the id.
+#[derive(Debug, Deserialize, ToSchema)]
+pub struct NewAsset {
+    name: Name,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    quantity: u32,
+    #[serde(default)]
+    location_id: Option<LocationId>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/assets",
+    responses((status = 200, description = "Every asset", body = [Asset]))
+)]
+pub async fn list(State(state): State<AppState>) -> Result<Json<Vec<Asset>>, ApiError> {
+    Ok(Json(state.assets.list().await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/assets",
+    request_body = NewAsset,
+    responses(
+        (status = 201, description = "The added asset", body = Asset),
+        (status = 422, description = "The body isn't a valid new asset"),
+    )
+)]
+pub async fn create(
+    State(state): State<AppState>,
+    Json(new): Json<NewAsset>,
+) -> Result<(StatusCode, Json<Asset>), ApiError> {
+    let mut asset = Asset::new(
+        AssetId(Uuid::now_v7()),
+        new.name,
+        new.description,
+        new.quantity,
+    );
+    asset.location_id = new.location_id;
+    let created = state.assets.create(asset).await?;
+    // The id is new, so nothing else can have appended to its log.
+    let event = AssetEvent::Created {
+        name: created.name.clone(),
+        quantity: created.quantity,
+    };
+    state.publish(created.id.0, 0, vec![event]).await?;
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+#[utoipa::path(
+    get,
+    path = "/assets/{id}",
+    params(("id" = Uuid, Path, description = "The asset's id")),
+    responses(
+        (status = 200, description = "The asset", body = Asset),
+        (status = 400, description = "The id isn't a UUID", body = ErrorBody),
+        (status = 404, description = "No asset has the id", body = ErrorBody),
+    )
+)]
+pub async fn fetch(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<Json<Asset>, ApiError> {
+    let found = state.assets.fetch(id.parse()?).await?;
+    Ok(Json(found.ok_or(RepositoryError::NotFound)?))
+}
+
+/// Replace the asset at `id` with the body, whose own id is ignored. The
+/// body's `version` must be the stored one, or the update is a 409.
+#[utoipa::path(
+    put,
+    path = "/assets/{id}",
+    params(("id" = Uuid, Path, description = "The asset's id")),
+    request_body = Asset,
+    responses(
+        (status = 200, description = "The stored asset, version bumped", body = Asset),
+        (status = 400, description = "The id isn't a UUID", body = ErrorBody),
+        (status = 404, description = "No asset has the id", body = ErrorBody),
+        (status = 409, description = "The asset changed since `version`", body = ErrorBody),
+    )
+)]
+pub async fn update(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(mut asset): Json<Asset>,
+) -> Result<Json<Asset>, ApiError> {
+    asset.id = id.parse()?;
+    Ok(Json(state.assets.update(asset).await?))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/assets/{id}",
+    params(("id" = Uuid, Path, description = "The asset's id")),
+    responses(
+        (status = 204, description = "The asset was removed"),
+        (status = 400, description = "The id isn't a UUID", body = ErrorBody),
+        (status = 404, description = "No asset has the id", body = ErrorBody),
+    )
+)]
+pub async fn delete(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    state.assets.delete(id.parse()?).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Run `cmd` against the asset at `id`, store the asset it leaves behind and
+/// publish the events it produced. A rejected command is a 422. Commands on
+/// the same asset wait for each other, so each sees the one before it.
+#[utoipa::path(
+    post,
+    path = "/assets/{id}/commands",
+    params(("id" = Uuid, Path, description = "The asset's id")),
+    request_body(
+        content = Object,
+        description = r#"An asset command, e.g. `{"AdjustQuantity": {"delta": 2}}`"#
+    ),
+    responses(
+        (status = 200, description = "The asset after the command", body = Asset),
+        (status = 404, description = "No asset has the id", body = ErrorBody),
+        (status = 422, description = "The asset rejected the command", body = ErrorBody),
+    )
+)]
+pub async fn command(
+    State(state): State<AppState>,
+    Path(id): Path<String>,
+    Json(cmd): Json<AssetCommand>,
+) -> Result<Json<Asset>, ApiError> {
+    let id: AssetId = id.parse()?;
+    let _running = state.lock(id.0).await;
+    let version = state.events.load(id.0).await?.len() as u64;
+    let mut asset = state
+        .assets
+        .fetch(id)
+        .await?
+        .ok_or(RepositoryError::NotFound)?;
+    let events = asset.handle_command(cmd)?;
+    for event in &events {
+        asset.apply_event(event)?;
+    }
+    let asset = state.assets.update(asset).await?;
+    state.publish(asset.id.0, version, events).await?;
+    Ok(Json(asset))
+}
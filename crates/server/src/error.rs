@@ -0,0 +1,71 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use stowr_core::{
+    error::{IdParseError, RepositoryError},
+    AggregateError,
+};
+use utoipa::ToSchema;
+
+/// The body of every error response.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ErrorBody {
+    /// What went wrong, for people rather than programs.
+    error: String,
+}
+
+/// Why a request failed, answered as a status code and an [ErrorBody].
+#[derive(Debug)]
+pub enum ApiError {
+    /// An id in the path isn't a UUID.
+    BadId(IdParseError),
+    Repository(RepositoryError),
+    /// The aggregate rejected a command.
+    Command(AggregateError),
+}
+
+impl From<IdParseError> for ApiError {
+    fn from(err: IdParseError) -> Self {
+        ApiError::BadId(err)
+    }
+}
+
+impl From<RepositoryError> for ApiError {
+    fn from(err: RepositoryError) -> Self {
+        ApiError::Repository(err)
+    }
+}
+
+impl From<AggregateError> for ApiError {
+    fn from(err: AggregateError) -> Self {
+        ApiError::Command(err)
+    }
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadId(_) => StatusCode::BAD_REQUEST,
+            ApiError::Repository(RepositoryError::NotFound) => StatusCode::NOT_FOUND,
+            ApiError::Repository(
+                RepositoryError::AlreadyExists | RepositoryError::Conflict { .. },
+            ) => StatusCode::CONFLICT,
+            ApiError::Repository(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            ApiError::Command(_) => StatusCode::UNPROCESSABLE_ENTITY,
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let error = match &self {
+            ApiError::BadId(err) => err.to_string(),
+            ApiError::Repository(err) => err.to_string(),
+            ApiError::Command(err) => err.to_string(),
+        };
+        (self.status(), Json(ErrorBody { error })).into_response()
+    }
+}
@@ -0,0 +1,50 @@
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use stowr_core::{asset::AssetEvent, eventstore::EventEnvelope};
+use tokio::sync::broadcast::{error::RecvError, Receiver};
+use tracing::warn;
+
+use crate::AppState;
+
+/// Upgrade to a WebSocket that is sent every event, as a JSON
+/// [EventEnvelope], from the moment it connects.
+pub async fn subscribe(ws: WebSocketUpgrade, State(state): State<AppState>) -> Response {
+    let feed = state.feed.subscribe();
+    ws.on_upgrade(move |socket| forward(socket, feed))
+}
+
+/// Send each event from `feed` to `socket` until the client goes away, which
+/// drops `feed` and so ends the subscription.
+async fn forward(mut socket: WebSocket, mut feed: Receiver<EventEnvelope<AssetEvent>>) {
+    loop {
+        tokio::select! {
+            envelope = feed.recv() => match envelope {
+                Ok(envelope) => {
+                    let json = match serde_json::to_string(&envelope) {
+                        Ok(json) => json,
+                        Err(err) => {
+                            warn!("couldn't serialize event {}: {err}", envelope.event_id);
+                            continue;
+                        }
+                    };
+                    if socket.send(Message::Text(json.into())).await.is_err() {
+                        break;
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    warn!("an events subscriber fell behind and missed {missed} events");
+                }
+                Err(RecvError::Closed) => break,
+            },
+            message = socket.recv() => match message {
+                Some(Ok(Message::Close(_)) | Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+}
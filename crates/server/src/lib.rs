@@ -0,0 +1,124 @@
+//! stowr's repositories served as a JSON REST API.
+
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use std::sync::Arc;
+use stowr_core::{
+    asset::{Asset, AssetEvent, AssetId},
+    error::RepositoryError,
+    eventstore::{EventEnvelope, EventStore},
+    Location, Repository,
+};
+use tokio::sync::{broadcast, Mutex, MutexGuard};
+use utoipa::OpenApi;
+use uuid::Uuid;
+
+mod assets;
+mod error;
+mod events;
+
+pub use error::{ApiError, ErrorBody};
+
+/// Where the API keeps its assets.
+pub type Assets = Arc<dyn Repository<Entity = Asset, Id = AssetId> + Send + Sync>;
+
+/// How many events a slow `/events` subscriber may fall behind by before it
+/// starts missing them.
+const FEED_CAPACITY: usize = 256;
+
+/// How many locks commands are spread over; see [AppState::lock].
+const COMMAND_LOCKS: usize = 64;
+
+/// What every handler can reach.
+#[derive(Clone)]
+pub struct AppState {
+    pub assets: Assets,
+    /// Every event a command has produced, per asset.
+    pub events: Arc<EventStore<Asset>>,
+    /// Each event as it is recorded, for the `/events` subscribers.
+    pub feed: broadcast::Sender<EventEnvelope<AssetEvent>>,
+    /// A fixed set of locks, each held while a command on one of the assets
+    /// it covers runs; see [AppState::lock].
+    commands: Arc<[Mutex<()>]>,
+}
+
+impl AppState {
+    pub fn new(
+        assets: impl Repository<Entity = Asset, Id = AssetId> + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            assets: Arc::new(assets),
+            events: Arc::new(EventStore::new()),
+            feed: broadcast::channel(FEED_CAPACITY).0,
+            commands: (0..COMMAND_LOCKS).map(|_| Mutex::new(())).collect(),
+        }
+    }
+
+    /// Wait for any other command on `aggregate_id` to finish, and keep
+    /// others out until the guard is dropped. Commands on an asset run one
+    /// at a time, so the event log can't move between reading its version
+    /// and appending to it.
+    ///
+    /// Assets share a fixed number of locks, so the ids clients send can't
+    /// grow the server's memory; a command may also wait for one on an
+    /// unrelated asset that shares its lock.
+    pub async fn lock(&self, aggregate_id: Uuid) -> MutexGuard<'_, ()> {
+        let stripe = aggregate_id.as_u128() % self.commands.len() as u128;
+        self.commands[stripe as usize].lock().await
+    }
+
+    /// Record `events` in the event store at `expected`, the version of the
+    /// asset's log the command was handled against, and send their envelopes
+    /// to every `/events` subscriber.
+    pub async fn publish(
+        &self,
+        aggregate_id: Uuid,
+        expected: u64,
+        events: Vec<AssetEvent>,
+    ) -> Result<(), RepositoryError> {
+        for envelope in self.events.append(aggregate_id, expected, events).await? {
+            // Nobody listening isn't an error.
+            let _ = self.feed.send(envelope);
+        }
+        Ok(())
+    }
+}
+
+/// The OpenAPI description of every REST route (everything but `/events`),
+/// served at `/openapi.json`.
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "stowr"),
+    paths(
+        assets::list,
+        assets::create,
+        assets::fetch,
+        assets::update,
+        assets::delete,
+        assets::command,
+    ),
+    components(schemas(Asset, Location, ErrorBody))
+)]
+pub struct ApiDoc;
+
+async fn openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+/// Every route of the API, over `state`.
+pub fn router(state: AppState) -> Router {
+    Router::new()
+        .route("/assets", get(assets::list).post(assets::create))
+        .route(
+            "/assets/{id}",
+            get(assets::fetch)
+                .put(assets::update)
+                .delete(assets::delete),
+        )
+        .route("/assets/{id}/commands", post(assets::command))
+        .route("/events", get(events::subscribe))
+        .route("/openapi.json", get(openapi))
+        .with_state(state)
+}
@@ -0,0 +1,41 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use stowr_core::{
+    asset::{Asset, AssetId},
+    db::{SqliteRepository, TracingRepository},
+    logger::{self, LevelFilter},
+};
+use stowr_server::{router, AppState};
+use tokio::net::TcpListener;
+use tracing::info;
+
+/// Serve stowr's assets as a JSON REST API
+#[derive(Parser, Debug)]
+#[command(version, about, long_about = None)]
+struct Args {
+    /// SQLite database to use, as a connection URL
+    #[arg(long, default_value = "sqlite://stowr.db?mode=rwc")]
+    db: String,
+
+    /// Address to listen on
+    #[arg(long, default_value = "127.0.0.1:3000")]
+    addr: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    logger::init(LevelFilter::INFO);
+
+    let assets = SqliteRepository::<Asset, AssetId>::connect(&args.db)
+        .await
+        .with_context(|| format!("could not open database {}", args.db))?;
+    let app = router(AppState::new(TracingRepository::new(assets)));
+
+    let listener = TcpListener::bind(&args.addr)
+        .await
+        .with_context(|| format!("could not listen on {}", args.addr))?;
+    info!("listening on {}", args.addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
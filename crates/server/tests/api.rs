@@ -0,0 +1,251 @@
+use async_trait::async_trait;
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use http_body_util::BodyExt;
+use serde_json::{json, Value};
+use stowr_core::{
+    asset::{Asset, AssetEvent, AssetId},
+    db::InMemoryRepository,
+    error::RepositoryError,
+    Page, Repository,
+};
+use stowr_server::{router, AppState};
+use tokio::task::yield_now;
+use tower::ServiceExt;
+
+fn app() -> Router {
+    router(AppState::new(InMemoryRepository::<Asset>::new()))
+}
+
+/// Send one request to `app`, returning the status and the JSON body (or
+/// `Value::Null` for an empty one).
+async fn send(app: &Router, method: Method, uri: &str, body: Option<Value>) -> (StatusCode, Value) {
+    let request = Request::builder().method(method).uri(uri);
+    let request = match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(body.to_string())),
+        None => request.body(Body::empty()),
+    };
+    let response = app.clone().oneshot(request.unwrap()).await.unwrap();
+    let status = response.status();
+    let bytes = response.into_body().collect().await.unwrap().to_bytes();
+    let json = if bytes.is_empty() {
+        Value::Null
+    } else {
+        serde_json::from_slice(&bytes).unwrap()
+    };
+    (status, json)
+}
+
+#[tokio::test]
+async fn created_assets_can_be_fetched_and_listed() {
+    let app = app();
+    let (status, created) = send(
+        &app,
+        Method::POST,
+        "/assets",
+        Some(json!({ "name": "Cordless drill", "quantity": 2 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let id = created["id"].as_str().unwrap();
+
+    let (status, fetched) = send(&app, Method::GET, &format!("/assets/{id}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(fetched, created);
+    assert_eq!(fetched["name"], "Cordless drill");
+    assert_eq!(fetched["quantity"], 2);
+
+    let (_, listed) = send(&app, Method::GET, "/assets", None).await;
+    assert_eq!(listed, json!([created]));
+}
+
+#[tokio::test]
+async fn unknown_ids_are_not_found_and_bad_ids_are_rejected() {
+    let app = app();
+    let unknown = "/assets/0199e1a2-6f3c-7d10-a4b2-3c5e8f9d0a11";
+    let (status, body) = send(&app, Method::GET, unknown, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(body["error"], "entity not found");
+    let (status, _) = send(&app, Method::DELETE, unknown, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send(&app, Method::GET, "/assets/not-an-id", None).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn stale_updates_conflict_and_deletes_remove() {
+    let app = app();
+    let (_, created) = send(
+        &app,
+        Method::POST,
+        "/assets",
+        Some(json!({ "name": "Saw" })),
+    )
+    .await;
+    let uri = format!("/assets/{}", created["id"].as_str().unwrap());
+
+    let mut renamed = created.clone();
+    renamed["name"] = json!("Table saw");
+    let (status, updated) = send(&app, Method::PUT, &uri, Some(renamed.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["name"], "Table saw");
+    assert_eq!(updated["version"], 1);
+
+    let (status, _) = send(&app, Method::PUT, &uri, Some(renamed)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = send(&app, Method::DELETE, &uri, None).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = send(&app, Method::GET, &uri, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn commands_update_the_asset_or_are_rejected() {
+    let app = app();
+    let (_, created) = send(
+        &app,
+        Method::POST,
+        "/assets",
+        Some(json!({ "name": "Tape", "quantity": 1 })),
+    )
+    .await;
+    let uri = format!("/assets/{}/commands", created["id"].as_str().unwrap());
+
+    let add = json!({ "AdjustQuantity": { "delta": 2 } });
+    let (status, updated) = send(&app, Method::POST, &uri, Some(add)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["quantity"], 3);
+
+    let overdraw = json!({ "AdjustQuantity": { "delta": -4 } });
+    let (status, body) = send(&app, Method::POST, &uri, Some(overdraw)).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(body["error"].as_str().unwrap().contains("negative"));
+}
+
+/// An in-memory repository that yields before every fetch and update, so
+/// concurrent requests interleave the way they would against a database.
+#[derive(Default)]
+struct Yielding(InMemoryRepository<Asset>);
+
+#[async_trait]
+impl Repository for Yielding {
+    type Entity = Asset;
+    type Id = AssetId;
+
+    async fn create(&self, asset: Asset) -> Result<Asset, RepositoryError> {
+        self.0.create(asset).await
+    }
+
+    async fn create_many(&self, assets: Vec<Asset>) -> Result<Vec<Asset>, RepositoryError> {
+        self.0.create_many(assets).await
+    }
+
+    async fn fetch(&self, id: AssetId) -> Result<Option<Asset>, RepositoryError> {
+        yield_now().await;
+        self.0.fetch(id).await
+    }
+
+    async fn fetch_many(&self, ids: &[AssetId]) -> Result<Vec<Asset>, RepositoryError> {
+        self.0.fetch_many(ids).await
+    }
+
+    async fn update(&self, asset: Asset) -> Result<Asset, RepositoryError> {
+        yield_now().await;
+        self.0.update(asset).await
+    }
+
+    async fn delete(&self, id: AssetId) -> Result<(), RepositoryError> {
+        self.0.delete(id).await
+    }
+
+    async fn list(&self) -> Result<Vec<Asset>, RepositoryError> {
+        self.0.list().await
+    }
+
+    async fn list_page(&self, page: Page) -> Result<Vec<Asset>, RepositoryError> {
+        self.0.list_page(page).await
+    }
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn concurrent_commands_all_land_in_the_event_log() {
+    let state = AppState::new(Yielding::default());
+    let app = router(state.clone());
+    let (_, created) = send(
+        &app,
+        Method::POST,
+        "/assets",
+        Some(json!({ "name": "Tape", "quantity": 0 })),
+    )
+    .await;
+    let id = created["id"].as_str().unwrap().to_string();
+    let uri = format!("/assets/{id}/commands");
+
+    let commands: Vec<_> = (0..20)
+        .map(|_| {
+            let (app, uri) = (app.clone(), uri.clone());
+            let add = json!({ "AdjustQuantity": { "delta": 1 } });
+            tokio::spawn(async move { send(&app, Method::POST, &uri, Some(add)).await.0 })
+        })
+        .collect();
+    for command in commands {
+        assert_eq!(command.await.unwrap(), StatusCode::OK);
+    }
+
+    let (_, fetched) = send(&app, Method::GET, &format!("/assets/{id}"), None).await;
+    assert_eq!(fetched["quantity"], 20);
+    let log = state.events.load(id.parse().unwrap()).await.unwrap();
+    let sequences: Vec<u64> = log.iter().map(|e| e.sequence).collect();
+    assert_eq!(sequences, (1..=21).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn created_assets_start_their_event_log() {
+    let state = AppState::new(InMemoryRepository::<Asset>::new());
+    let app = router(state.clone());
+    let (_, created) = send(
+        &app,
+        Method::POST,
+        "/assets",
+        Some(json!({ "name": "Level", "quantity": 3 })),
+    )
+    .await;
+
+    let id: AssetId = created["id"].as_str().unwrap().parse().unwrap();
+    let log = state.events.load(id.0).await.unwrap();
+    assert_eq!(log.len(), 1);
+    assert!(matches!(
+        &log[0].event,
+        AssetEvent::Created { name, quantity } if name.as_str() == "Level" && quantity.get() == 3
+    ));
+}
+
+#[tokio::test]
+async fn openapi_document_describes_assets() {
+    let (status, doc) = send(&app(), Method::GET, "/openapi.json", None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let schemas = &doc["components"]["schemas"];
+    assert_eq!(schemas["Asset"]["type"], "object");
+    assert_eq!(schemas["Name"]["maxLength"], 255);
+    let created_at = &schemas["Asset"]["properties"]["created_at"];
+    assert_eq!(
+        (&created_at["type"], &created_at["format"]),
+        (&json!("string"), &json!("date-time"))
+    );
+    assert_eq!(
+        schemas["RepositoryId"],
+        json!({ "type": "string", "format": "uuid" })
+    );
+    let path = &doc["paths"]["/assets/{id}"];
+    for method in ["get", "put", "delete"] {
+        assert!(path[method].is_object(), "no {method} /assets/{{id}}");
+    }
+}
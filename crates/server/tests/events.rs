@@ -0,0 +1,70 @@
+use axum::{
+    body::Body,
+    http::{header, Request, StatusCode},
+};
+use futures_util::StreamExt;
+use serde_json::json;
+use std::time::Duration;
+use stowr_core::{
+    asset::{Asset, AssetEvent, AssetId},
+    db::InMemoryRepository,
+    eventstore::EventEnvelope,
+};
+use stowr_server::{router, AppState};
+use tokio::{net::TcpListener, time::timeout};
+use tower::ServiceExt;
+use uuid::Uuid;
+
+#[tokio::test]
+async fn commands_are_streamed_to_event_subscribers() {
+    let state = AppState::new(InMemoryRepository::<Asset>::new());
+    let drill = Asset::new(
+        AssetId(Uuid::now_v7()),
+        "Drill".parse().unwrap(),
+        None::<String>,
+        1u32,
+    );
+    let drill = state.assets.create(drill).await.unwrap();
+
+    let app = router(state.clone());
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = axum::serve(listener, app.clone());
+    tokio::spawn(async move { server.await.unwrap() });
+    let (mut socket, _) = tokio_tungstenite::connect_async(format!("ws://{addr}/events"))
+        .await
+        .unwrap();
+
+    let request = Request::post(format!("/assets/{}/commands", drill.id))
+        .header(header::CONTENT_TYPE, "application/json")
+        .body(Body::from(
+            json!({ "AdjustQuantity": { "delta": 2 } }).to_string(),
+        ))
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let message = timeout(Duration::from_secs(5), socket.next())
+        .await
+        .expect("no event within 5s")
+        .unwrap()
+        .unwrap();
+    let envelope: EventEnvelope<AssetEvent> =
+        serde_json::from_str(message.to_text().unwrap()).unwrap();
+    assert_eq!(envelope.aggregate_id, drill.id.0);
+    assert_eq!(envelope.sequence, 1);
+    assert!(matches!(
+        envelope.event,
+        AssetEvent::QuantityAdjusted { delta: 2 }
+    ));
+
+    socket.close(None).await.unwrap();
+    let unsubscribed = async {
+        while state.feed.receiver_count() > 0 {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    };
+    timeout(Duration::from_secs(5), unsubscribed)
+        .await
+        .expect("the subscription outlived the socket");
+}